@@ -1,6 +1,21 @@
+use crate::core::checker::set_checker;
+use crate::core::config::Config;
+use crate::core::generate::*;
+use crate::core::group::*;
+use crate::core::interactor::{set_interactive, set_interactor};
+use crate::core::invoke::{invoke, SolutionReport};
+use crate::core::polygon::{export_polygon, import_polygon};
+use crate::core::render::*;
 use crate::core::solution::*;
 use crate::core::source::*;
-use crate::core::{create_problem_dir, is_valid_problem_name, reformat_valid_name, ProblemConfig};
+use crate::core::statement::*;
+use crate::core::stress::stress;
+use crate::core::testcase::*;
+use crate::core::tutorial::*;
+use crate::core::validate::validate_tests;
+use crate::core::{
+    create_problem_dir, is_valid_problem_name, reformat_valid_name, set_memory, ProblemConfig,
+};
 
 use std::env::current_dir;
 use std::fs::File;
@@ -33,7 +48,59 @@ pub enum Command {
 
     /// Run a Program
     #[command(subcommand)]
-    Run(BuildArg), // TODO: make it its own enum
+    Run(RunArg),
+
+    /// Runs every solution against every testcase and checks the expected verdicts
+    Invoke,
+
+    /// Runs the validator on the input of every testcase
+    Validate,
+
+    /// Lists components of the problem
+    #[command(subcommand)]
+    List(ListArg),
+
+    /// Generates parts of the problem
+    #[command(subcommand)]
+    Gen(GenArg),
+
+    /// Works with the statement of the problem
+    #[command(subcommand)]
+    Statement(StatementArg),
+
+    /// Works with the tutorial of the problem
+    #[command(subcommand)]
+    Tutorial(TutorialArg),
+
+    /// Exports the problem to another format
+    #[command(subcommand)]
+    Export(ExportArg),
+
+    /// Creates a problem from another format
+    #[command(subcommand)]
+    Import(ImportArg),
+
+    /// Compares two solutions on tests generated with increasing seeds until they disagree
+    Stress {
+        /// The generator, which gets the seed as its last argument
+        generator: String,
+        /// The solution whose output is taken as the answer
+        first: PathBuf,
+        /// The solution judged against the first one
+        second: PathBuf,
+        /// The first seed
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// The number of tests to try, unlimited if not given
+        #[arg(short = 'n', long)]
+        iterations: Option<u64>,
+        /// Adds the test the solutions disagree on to the testcases
+        #[arg(long)]
+        save: bool,
+        /// Arguments passed to the generator before the seed
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
 
     /// Info
     Info,
@@ -46,38 +113,242 @@ pub enum BuildArg {
     All,
 }
 
+#[derive(Subcommand)]
+pub enum RunArg {
+    Solution {
+        path: PathBuf,
+        #[command(flatten)]
+        io: RunIo,
+    },
+    Source {
+        path: PathBuf,
+        #[command(flatten)]
+        io: RunIo,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct RunIo {
+    /// File fed to the standard input of the program
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+    /// File the standard output of the program is written to
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// File the standard error of the program is written to
+    #[arg(short, long)]
+    error: Option<PathBuf>,
+    /// Extra arguments passed to the program
+    #[arg(last = true)]
+    args: Vec<String>,
+}
+
+impl From<RunIo> for RunOptions {
+    fn from(io: RunIo) -> Self {
+        RunOptions {
+            args: io.args.into_iter().map(Into::into).collect(),
+            stdin: io.input,
+            stdout: io.output,
+            stderr: io.error,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct SectionArg {
+    section: Section,
+    /// File the section is copied from. The section is opened in $EDITOR if not given
+    path: Option<PathBuf>,
+    /// The language of the statement, the first statement of the problem by default
+    #[arg(long)]
+    lang: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct StatementPart {
+    /// The section to remove, the whole statement if not given
+    section: Option<Section>,
+    /// The language of the statement, the first statement of the problem by default
+    #[arg(long)]
+    lang: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum StatementArg {
+    /// Renders the statement with its samples to a standalone document. A LaTeX document
+    /// reads its samples from the "example.NN" files written next to it
+    Render {
+        #[command(flatten)]
+        format: RenderFormat,
+        /// File the document is written to, "bin/statement.<lang>.<format>" by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The language of the statement, the first statement of the problem by default
+        #[arg(long)]
+        lang: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TutorialArg {
+    /// Renders the tutorial with the solutions it embeds to a standalone document
+    Render {
+        #[command(flatten)]
+        format: RenderFormat,
+        /// File the document is written to, "bin/tutorial.<lang>.<format>" by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The language of the tutorial
+        #[arg(long, default_value = DEFAULT_LANGUAGE)]
+        lang: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportArg {
+    /// Writes a zipped Polygon package with problem.xml, which Polygon can import
+    Polygon {
+        /// File the package is written to, "bin/<problem>.zip" by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportArg {
+    /// Creates a problem from a Polygon package, zipped or extracted
+    Polygon {
+        package: PathBuf,
+        /// The directory of the new problem, named after the short name of the package by default
+        directory: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct TutorialFile {
+    /// File the tutorial is copied from. The tutorial is opened in $EDITOR if not given
+    path: Option<PathBuf>,
+    /// The language of the tutorial
+    #[arg(long, default_value = DEFAULT_LANGUAGE)]
+    lang: String,
+}
+
+#[derive(clap::Args)]
+pub struct LanguageArg {
+    /// The language of the tutorial
+    #[arg(long, default_value = DEFAULT_LANGUAGE)]
+    lang: String,
+}
+
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct RenderFormat {
+    /// Renders an HTML page, typesetting the math with MathJax
+    #[arg(long)]
+    html: bool,
+    /// Renders a LaTeX document for olymp.sty
+    #[arg(long)]
+    latex: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ListArg {
+    Tests,
+    Script,
+    Groups,
+    /// Lists the statements and the sections of translations that are out of date
+    Statements,
+}
+
+#[derive(Subcommand)]
+pub enum GenArg {
+    /// Writes the output of every testcase by running the main solution
+    Outputs,
+    /// Writes the input of every generated testcase by running the generation script
+    Tests,
+}
+
 #[derive(Subcommand)]
 pub enum AddArg {
-    Statement,
+    Statement {
+        /// The language of the statement
+        #[arg(long, default_value = DEFAULT_LANGUAGE)]
+        lang: String,
+        /// The language of the statement the new one is translated from
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Creates the tutorial in "text/tutorial". A line such as "{{solution ok.cpp}}"
+    /// in it is replaced by the source of the solution when rendered
+    Tutorial(LanguageArg),
     Solution {
         path: PathBuf,
-        #[arg(value_enum)]
-        verdict: Option<Verdict>,
+        /// The expected verdict: a verdict (ac, tle, wa, mle, re), verdicts separated
+        /// by '|' (tle|ac) or a preset (main, correct, rejected, failed,
+        /// time-limit-or-accepted, time-limit-or-memory-limit)
+        verdict: Option<ExpectedVerdict>,
+        /// The profile or language of the solution, guessed from its extension by default
+        #[arg(long)]
+        lang: Option<String>,
     },
     Source {
         path: PathBuf,
+        /// The profile or language of the source, guessed from its extension by default
+        #[arg(long)]
+        lang: Option<String>,
     }, // TODO: make it path: name
+    /// Adds testcases from a file, every file in a directory or a pattern such as "tests/*.in".
+    /// The input is read from stdin if no path is given.
+    Test {
+        path: Option<PathBuf>,
+        #[arg(long)]
+        sample: bool,
+    },
+    /// Adds a line such as "gen 10 20 --seed 3 > $" to the generation script
+    Script { line: GeneratorCall },
+    /// Adds a group of testcases worth some points
+    Group {
+        name: String,
+        points: f64,
+        /// Groups that must be passed for this group to score, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        depends: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum SetArg {
-    Statement,
+    Statement(SectionArg),
+    Tutorial(TutorialFile),
     MainSolution { path: PathBuf },
     Validator { path: PathBuf },
     Title { title: String },
     Time { time: f32 },
-    Checker { path: PathBuf },
+    Memory { memory: u32 },
+    Checker { name: String },
+    Interactor { path: PathBuf },
+    Interactive,
+    Group { name: String, tests: Vec<usize> },
+    Score { path: PathBuf, score: f64 },
     Tags { tags: String },
+    Sample { test: usize },
+    Script { path: PathBuf },
 }
 
 #[derive(Subcommand)]
 pub enum RemoveArg {
-    Statement,
+    Statement(StatementPart),
+    Tutorial(LanguageArg),
     Solution { path: PathBuf },
     Source { path: PathBuf }, // TODO: make it path: name
+    Test { test: usize },
+    Sample { test: usize },
+    Interactive,
+    Group { name: String },
+    Score { path: PathBuf },
 }
 
-pub fn handle_command(command: Option<Command>) {
+pub fn handle_command(command: Option<Command>, config: Config) {
     match command {
         Some(Command::New { name }) => {
             create_problem_command(name);
@@ -85,11 +356,15 @@ pub fn handle_command(command: Option<Command>) {
         Some(Command::Info) => {
             print_problem_info();
         }
-        Some(Command::Add(AddArg::Source { path })) => {
-            add_source_command(&path);
+        Some(Command::Add(AddArg::Source { path, lang })) => {
+            add_source_command(&path, lang.as_deref(), &config);
         }
-        Some(Command::Add(AddArg::Solution { path, verdict })) => {
-            add_solution_command(&path, verdict);
+        Some(Command::Add(AddArg::Solution {
+            path,
+            verdict,
+            lang,
+        })) => {
+            add_solution_command(&path, verdict, lang.as_deref(), &config);
         }
         Some(Command::Remove(RemoveArg::Source { path })) => {
             remove_source_command(&path);
@@ -99,12 +374,81 @@ pub fn handle_command(command: Option<Command>) {
             // at the end
             remove_solution_command(&path);
         }
+        Some(Command::Add(AddArg::Statement { lang, from })) => {
+            add_statement_command(&lang, from.as_deref())
+        }
+        Some(Command::Set(SetArg::Statement(arg))) => set_statement_command(arg),
+        Some(Command::Remove(RemoveArg::Statement(part))) => remove_statement_command(part),
+        Some(Command::List(ListArg::Statements)) => list_statements_command(),
+        Some(Command::Add(AddArg::Tutorial(arg))) => add_tutorial_command(&arg.lang),
+        Some(Command::Set(SetArg::Tutorial(arg))) => {
+            set_tutorial_command(&arg.lang, arg.path.as_deref())
+        }
+        Some(Command::Remove(RemoveArg::Tutorial(arg))) => remove_tutorial_command(&arg.lang),
+        Some(Command::Export(ExportArg::Polygon { output })) => export_polygon_command(output),
+        Some(Command::Import(ImportArg::Polygon { package, directory })) => {
+            import_polygon_command(&package, directory, &config)
+        }
+        Some(Command::Tutorial(TutorialArg::Render {
+            format,
+            output,
+            lang,
+        })) => render_tutorial_command(format, output, &lang),
+        Some(Command::Add(AddArg::Test { path, sample })) => add_test_command(path, sample),
+        Some(Command::Remove(RemoveArg::Test { test })) => remove_test_command(test),
+        Some(Command::Set(SetArg::Sample { test })) => set_sample_command(test, true),
+        Some(Command::Remove(RemoveArg::Sample { test })) => set_sample_command(test, false),
+        Some(Command::List(ListArg::Tests)) => list_tests_command(),
+        Some(Command::Add(AddArg::Script { line })) => add_script_line_command(line),
+        Some(Command::Set(SetArg::Script { path })) => set_script_command(&path),
+        Some(Command::List(ListArg::Script)) => list_script_command(),
         Some(Command::Set(SetArg::Validator { path })) => set_validator_command(&path),
+        Some(Command::Set(SetArg::Checker { name })) => set_checker_command(&name),
+        Some(Command::Set(SetArg::Interactor { path })) => set_interactor_command(&path),
+        Some(Command::Set(SetArg::Interactive)) => set_interactive_command(true),
+        Some(Command::Remove(RemoveArg::Interactive)) => set_interactive_command(false),
+        Some(Command::Add(AddArg::Group {
+            name,
+            points,
+            depends,
+        })) => add_group_command(&name, points, &depends),
+        Some(Command::Remove(RemoveArg::Group { name })) => remove_group_command(&name),
+        Some(Command::Set(SetArg::Group { name, tests })) => set_group_command(&name, &tests),
+        Some(Command::List(ListArg::Groups)) => list_groups_command(),
+        Some(Command::Set(SetArg::Score { path, score })) => set_score_command(&path, Some(score)),
+        Some(Command::Remove(RemoveArg::Score { path })) => set_score_command(&path, None),
+        Some(Command::Set(SetArg::Memory { memory })) => set_memory_command(memory),
+        Some(Command::Set(SetArg::MainSolution { path })) => set_main_solution_command(&path),
         Some(Command::Build(BuildArg::All)) => build_all_command(),
         Some(Command::Build(BuildArg::Solution { path })) => build_solution_command(&path),
         Some(Command::Build(BuildArg::Source { path })) => build_source_command(&path),
-        Some(Command::Run(BuildArg::Solution { path })) => run_solution_command(&path),
-        Some(Command::Run(BuildArg::Source { path })) => run_source_command(&path),
+        Some(Command::Run(RunArg::Solution { path, io })) => run_solution_command(&path, io.into()),
+        Some(Command::Run(RunArg::Source { path, io })) => run_source_command(&path, io.into()),
+        Some(Command::Invoke) => invoke_command(),
+        Some(Command::Validate) => validate_command(),
+        Some(Command::Gen(GenArg::Outputs)) => gen_outputs_command(),
+        Some(Command::Gen(GenArg::Tests)) => gen_tests_command(),
+        Some(Command::Statement(StatementArg::Render {
+            format,
+            output,
+            lang,
+        })) => render_statement_command(format, output, lang.as_deref()),
+        Some(Command::Stress {
+            generator,
+            first,
+            second,
+            seed,
+            iterations,
+            save,
+            args,
+        }) => {
+            let call = GeneratorCall {
+                generator,
+                args,
+                test: None,
+            };
+            stress_command(&call, &first, &second, seed, iterations, save);
+        }
         None => {}
         _ => unimplemented!(),
     }
@@ -137,6 +481,14 @@ pub fn create_problem_command(name: String) {
     stdout().flush().unwrap();
     let time = read_input(1.0);
 
+    print!("Max Memory in Megabytes (Default is 256): ");
+    stdout().flush().unwrap();
+    let memory = read_input(256);
+
+    print!("Interactive (Default is false): ");
+    stdout().flush().unwrap();
+    let interactive = read_input(false);
+
     print!("Tags Saperated by commas (Default is Empty): ");
     stdout().flush().unwrap();
     let tags = read_input(String::new());
@@ -152,12 +504,19 @@ pub fn create_problem_command(name: String) {
     let config = ProblemConfig {
         title,
         time,
+        memory,
         tags,
         sources: Vec::new(),
         solutions: Vec::new(),
         testcases: Vec::new(),
+        groups: Vec::new(),
         checker: None,
         validator: None,
+        main_solution: None,
+        script: Vec::new(),
+        interactive,
+        interactor: None,
+        statements: Vec::new(),
     };
 
     println!("{config:?}");
@@ -173,11 +532,15 @@ pub fn print_problem_info() {
 
     println!("Title: {}", problem_config.title);
     println!("Time: {}", problem_config.time);
+    println!("Memory: {}", problem_config.memory);
     println!("Tags: {:?}", problem_config.tags);
     println!("Sources: {:?}", problem_config.sources);
     println!("Solutions: {:?}", problem_config.solutions);
     println!("Checker: {:?}", problem_config.checker);
+    println!("Interactive: {}", problem_config.interactive);
+    println!("Interactor: {:?}", problem_config.interactor);
     println!("Validator: {:?}", problem_config.validator);
+    println!("Main Solution: {:?}", problem_config.main_solution);
 }
 
 fn get_current_problem_directory() -> PathBuf {
@@ -188,17 +551,18 @@ fn get_current_problem_directory() -> PathBuf {
             .parent()
             .expect("Reached Maximum parent depth and didn't find problem_config.json");
     }
-    return path.to_owned();
+    path.to_owned()
 }
 
-fn add_source_command(path: &Path) {
+fn add_source_command(path: &Path, lang: Option<&str>, config: &Config) {
     let cpd = get_current_problem_directory();
+    let config = config.with_problem(&cpd).unwrap();
 
     if path.exists() && path.is_file() {
         let filename = path.file_name().unwrap().to_str().unwrap();
-        add_source(&cpd, filename, Some(&path)).unwrap();
+        add_source(&cpd, filename, Some(path), &config, lang).unwrap();
     } else if let Some(name) = path.file_name() {
-        add_source(&cpd, name.to_str().unwrap(), None).unwrap();
+        add_source(&cpd, name.to_str().unwrap(), None, &config, lang).unwrap();
     } else {
         eprintln!("Invalid input.");
         return;
@@ -213,15 +577,21 @@ fn remove_source_command(path: &Path) {
     println!("Done");
 }
 
-fn add_solution_command(path: &Path, verdict: Option<Verdict>) {
+fn add_solution_command(
+    path: &Path,
+    verdict: Option<ExpectedVerdict>,
+    lang: Option<&str>,
+    config: &Config,
+) {
     let cpd = get_current_problem_directory();
+    let config = config.with_problem(&cpd).unwrap();
     let verdict = verdict.unwrap_or_default();
 
     if path.exists() && path.is_file() {
         let filename = path.file_name().unwrap().to_str().unwrap();
-        add_solution(&cpd, filename, Some(&path), verdict).unwrap();
+        add_solution(&cpd, filename, Some(path), verdict, &config, lang).unwrap();
     } else if let Some(name) = path.file_name() {
-        add_solution(&cpd, name.to_str().unwrap(), None, verdict).unwrap();
+        add_solution(&cpd, name.to_str().unwrap(), None, verdict, &config, lang).unwrap();
     } else {
         eprintln!("Invalid input.");
         return;
@@ -236,12 +606,336 @@ fn remove_solution_command(path: &Path) {
     println!("Done");
 }
 
+fn add_statement_command(language: &str, from: Option<&str>) {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    add_statement(&cpd, language, from).unwrap();
+    println!("Done");
+}
+
+fn set_statement_command(arg: SectionArg) {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    let language = statement_language(&cpd, arg.lang.as_deref());
+    match arg.path {
+        Some(path) => set_section(&cpd, &language, arg.section, &path).unwrap(),
+        None => edit_section(&cpd, &language, arg.section).unwrap(),
+    }
+    println!("Done");
+}
+
+fn remove_statement_command(part: StatementPart) {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    let language = statement_language(&cpd, part.lang.as_deref());
+    remove_statement(&cpd, &language, part.section).unwrap();
+    println!("Done");
+}
+
+fn list_statements_command() {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    for statement in &config.statements {
+        let Some(source) = &statement.source else {
+            println!("{}", statement.language);
+            continue;
+        };
+
+        let stale: Vec<&str> = stale_sections(&cpd, statement)
+            .unwrap()
+            .iter()
+            .map(Section::name)
+            .collect();
+        if stale.is_empty() {
+            println!("{}: translated from {source}", statement.language);
+        } else {
+            println!(
+                "{}: translated from {source}, stale: {}",
+                statement.language,
+                stale.join(", ")
+            );
+        }
+    }
+}
+
+/// Resolves the language of a statement, which is the first statement if not given.
+/// Moves a statement from before statements had languages to the default language.
+fn migrate_statement_command(cpd: &Path) {
+    if migrate_statement(cpd).unwrap() {
+        println!("Moved the statement in text/statement to text/statement/{DEFAULT_LANGUAGE}");
+    }
+}
+
+fn statement_language(cpd: &Path, language: Option<&str>) -> String {
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+    find_statement(&config, language).unwrap().language.clone()
+}
+
+fn render_statement_command(format: RenderFormat, output: Option<PathBuf>, language: Option<&str>) {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+    let statement = find_statement(&config, language).unwrap();
+
+    let stale = stale_sections(&cpd, statement).unwrap();
+    if !stale.is_empty() {
+        let names: Vec<&str> = stale.iter().map(Section::name).collect();
+        eprintln!(
+            "Warning: the statement in {} is stale in {}",
+            statement.language,
+            names.join(", ")
+        );
+    }
+
+    let (document, extension) = if format.latex {
+        (render_latex(&cpd, &config, statement).unwrap(), "tex")
+    } else {
+        (render_html(&cpd, &config, statement).unwrap(), "html")
+    };
+    let output = output.unwrap_or(
+        cpd.join("bin")
+            .join(format!("statement.{}.{extension}", statement.language)),
+    );
+    if format.latex {
+        let dir = output.parent().filter(|dir| !dir.as_os_str().is_empty());
+        write_examples(dir.unwrap_or(Path::new(".")), &config).unwrap();
+    }
+    std::fs::write(&output, document).unwrap();
+    println!("Written to {}", output.display());
+}
+
+fn add_tutorial_command(language: &str) {
+    let cpd = get_current_problem_directory();
+    add_tutorial(&cpd, language).unwrap();
+    println!("Done");
+}
+
+fn set_tutorial_command(language: &str, path: Option<&Path>) {
+    let cpd = get_current_problem_directory();
+    set_tutorial(&cpd, language, path).unwrap();
+    println!("Done");
+}
+
+fn remove_tutorial_command(language: &str) {
+    let cpd = get_current_problem_directory();
+    remove_tutorial(&cpd, language).unwrap();
+    println!("Done");
+}
+
+fn render_tutorial_command(format: RenderFormat, output: Option<PathBuf>, language: &str) {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    let (document, extension) = if format.latex {
+        (
+            render_tutorial_latex(&cpd, &config, language).unwrap(),
+            "tex",
+        )
+    } else {
+        (
+            render_tutorial_html(&cpd, &config, language).unwrap(),
+            "html",
+        )
+    };
+    let output = output.unwrap_or(
+        cpd.join("bin")
+            .join(format!("tutorial.{language}.{extension}")),
+    );
+    std::fs::write(&output, document).unwrap();
+    println!("Written to {}", output.display());
+}
+
+fn export_polygon_command(output: Option<PathBuf>) {
+    let cpd = get_current_problem_directory();
+    migrate_statement_command(&cpd);
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    let name = cpd.file_name().unwrap().to_string_lossy();
+    let output = output.unwrap_or(cpd.join("bin").join(format!("{name}.zip")));
+    export_polygon(&cpd, &config, &output).unwrap();
+    println!("Written to {}", output.display());
+}
+
+fn import_polygon_command(package: &Path, directory: Option<PathBuf>, config: &Config) {
+    let (directory, warnings) = import_polygon(package, directory.as_deref(), config).unwrap();
+    for warning in &warnings {
+        eprintln!("Warning: {warning}");
+    }
+    println!("Imported to {}", directory.display());
+}
+
+fn add_test_command(path: Option<PathBuf>, sample: bool) {
+    let cpd = get_current_problem_directory();
+
+    let Some(path) = path else {
+        let n = add_test(&cpd, None, sample).unwrap();
+        println!("Added test {n}");
+        return;
+    };
+
+    let files = expand_test_files(&path).unwrap();
+    if files.is_empty() {
+        eprintln!("No files match {}", path.display());
+        return;
+    }
+
+    for file in files {
+        let n = add_test(&cpd, Some(&file), sample).unwrap();
+        println!("Added {} as test {n}", file.display());
+    }
+}
+
+fn remove_test_command(test: usize) {
+    let cpd = get_current_problem_directory();
+    remove_test(&cpd, test).unwrap();
+    println!("Done");
+}
+
+fn set_sample_command(test: usize, sample: bool) {
+    let cpd = get_current_problem_directory();
+    set_sample(&cpd, test, sample).unwrap();
+    println!("Done");
+}
+
+fn list_tests_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        let size = testcase.input_path.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut flags = Vec::new();
+        if testcase.sample {
+            flags.push("sample");
+        }
+        if testcase.generate {
+            flags.push("generated");
+        }
+        if !testcase.output_path.exists() {
+            flags.push("no output");
+        }
+        match &testcase.group {
+            Some(group) => println!("{:>3}: {size} bytes {flags:?} group {group}", i + 1),
+            None => println!("{:>3}: {size} bytes {flags:?}", i + 1),
+        }
+    }
+}
+
+fn add_group_command(name: &str, points: f64, dependencies: &[String]) {
+    let cpd = get_current_problem_directory();
+    add_group(&cpd, name, points, dependencies).unwrap();
+    println!("Done");
+}
+
+fn remove_group_command(name: &str) {
+    let cpd = get_current_problem_directory();
+    remove_group(&cpd, name).unwrap();
+    println!("Done");
+}
+
+fn set_group_command(name: &str, tests: &[usize]) {
+    let cpd = get_current_problem_directory();
+    set_group(&cpd, name, tests).unwrap();
+    println!("Done");
+}
+
+fn list_groups_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    for group in &config.groups {
+        let tests: Vec<usize> = config
+            .testcases
+            .iter()
+            .enumerate()
+            .filter(|(_, testcase)| testcase.group.as_ref() == Some(&group.name))
+            .map(|(i, _)| i + 1)
+            .collect();
+        print!("{}: {} points, tests {tests:?}", group.name, group.points);
+        if !group.dependencies.is_empty() {
+            print!(", depends on {}", group.dependencies.join(", "));
+        }
+        println!();
+    }
+}
+
+fn set_score_command(name: &Path, score: Option<f64>) {
+    let cpd = get_current_problem_directory();
+    set_expected_score(&cpd, name.file_name().unwrap().to_str().unwrap(), score).unwrap();
+    println!("Done");
+}
+
+fn add_script_line_command(line: GeneratorCall) {
+    let cpd = get_current_problem_directory();
+    add_script_line(&cpd, line).unwrap();
+    println!("Done");
+}
+
+fn set_script_command(path: &Path) {
+    let cpd = get_current_problem_directory();
+    let script = parse_script(&std::fs::read_to_string(path).unwrap()).unwrap();
+    set_script(&cpd, script).unwrap();
+    println!("Done");
+}
+
+fn list_script_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    for call in &config.script {
+        println!("{call}");
+    }
+}
+
 fn set_validator_command(name: &Path) {
     let cpd = get_current_problem_directory();
     set_validator(&cpd, name.file_name().unwrap().to_str().unwrap()).unwrap();
     println!("Done");
 }
 
+fn set_checker_command(name: &str) {
+    let cpd = get_current_problem_directory();
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(name);
+    set_checker(&cpd, name).unwrap();
+    println!("Done");
+}
+
+fn set_interactor_command(name: &Path) {
+    let cpd = get_current_problem_directory();
+    set_interactor(&cpd, name.file_name().unwrap().to_str().unwrap()).unwrap();
+    println!("Done");
+}
+
+fn set_interactive_command(interactive: bool) {
+    let cpd = get_current_problem_directory();
+    set_interactive(&cpd, interactive).unwrap();
+    println!("Done");
+}
+
+fn set_main_solution_command(name: &Path) {
+    let cpd = get_current_problem_directory();
+    set_main_solution(&cpd, name.file_name().unwrap().to_str().unwrap()).unwrap();
+    println!("Done");
+}
+
+fn set_memory_command(memory: u32) {
+    let cpd = get_current_problem_directory();
+    set_memory(&cpd, memory).unwrap();
+    println!("Done");
+}
+
 fn build_all_command() {
     let cpd = get_current_problem_directory();
     let config =
@@ -306,12 +1000,13 @@ fn build_source_command(source: &Path) {
     println!("Done");
 }
 
-fn run_source_command(source: &Path) {
+fn run_source_command(source: &Path, options: RunOptions) {
     let cpd = get_current_problem_directory();
     let config =
         ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+    let limits = config.limits();
 
-    config
+    let report = config
         .sources
         .iter()
         .find(|item| item.source.file_name().eq(&source.file_name()))
@@ -322,17 +1017,18 @@ fn run_source_command(source: &Path) {
             );
             panic!("An Error has occoured");
         })
-        .run(&cpd)
+        .run(&cpd, &limits, &options)
         .unwrap();
-    println!("Done");
+    print_run_report(&report, &limits);
 }
 
-fn run_solution_command(source: &Path) {
+fn run_solution_command(source: &Path, options: RunOptions) {
     let cpd = get_current_problem_directory();
     let config =
         ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+    let limits = config.limits();
 
-    config
+    let report = config
         .solutions
         .iter()
         .find(|item| item.sourcefile.source.file_name().eq(&source.file_name()))
@@ -344,7 +1040,208 @@ fn run_solution_command(source: &Path) {
             panic!("An Error has occoured");
         })
         .sourcefile
-        .run(&cpd)
+        .run(&cpd, &limits, &options)
         .unwrap();
-    println!("Done");
+    print_run_report(&report, &limits);
+}
+
+fn print_run_report(report: &RunReport, limits: &Limits) {
+    if let Some(verdict) = report.limit_verdict(limits) {
+        println!("Verdict: {verdict}");
+    }
+    match report.crash() {
+        Some(crash) => println!("Crashed with {crash}"),
+        None => println!("Exited successfully"),
+    }
+    println!(
+        "CPU time: {:.3}s, Wall time: {:.3}s, Memory: {}KB",
+        report.cpu_time.as_secs_f64(),
+        report.wall_time.as_secs_f64(),
+        report.peak_memory
+    );
+    if let Some(stdout) = &report.stdout {
+        println!("Output: {}", stdout.display());
+    }
+    if let Some(stderr) = &report.stderr {
+        println!("Error: {}", stderr.display());
+    }
+}
+
+fn validate_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    let results = validate_tests(&cpd, &config).unwrap();
+    let mut valid = true;
+    for (i, error) in results.iter().enumerate() {
+        match error {
+            None => println!("Test {}: OK", i + 1),
+            Some(error) => {
+                valid = false;
+                println!("Test {}: INVALID: {error}", i + 1);
+            }
+        }
+    }
+
+    if !valid {
+        std::process::exit(1);
+    }
+}
+
+fn invoke_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    if config.testcases.is_empty() {
+        eprintln!("The problem has no testcases.");
+        return;
+    }
+
+    let reports = invoke(&cpd, &config).unwrap();
+    print_verdict_matrix(&reports, config.testcases.len());
+    if !config.groups.is_empty() {
+        print_score_matrix(&reports, &config);
+    }
+
+    let failed: Vec<&SolutionReport> = reports.iter().filter(|r| !r.is_expected()).collect();
+    for report in &failed {
+        if !report.expected.accepts(&report.verdicts()) {
+            eprintln!(
+                "FAILED: {} is expected to be {} but got {:?}",
+                report.name,
+                report.expected,
+                report.verdicts()
+            );
+        }
+        if let (false, Some(score)) = (report.score_matches(), report.expected_score) {
+            eprintln!(
+                "FAILED: {} is expected to score {score} but scored {}",
+                report.name,
+                report.total_score()
+            );
+        }
+    }
+
+    if !failed.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn print_verdict_matrix(reports: &[SolutionReport], tests: usize) {
+    let width = reports
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Solution".len());
+
+    print!("{:width$}", "Solution");
+    for i in 1..=tests {
+        print!(" {:>4}", i);
+    }
+    println!(" | Expected");
+
+    for report in reports {
+        print!("{:width$}", report.name);
+        for result in &report.results {
+            print!(" {:>4}", result.verdict);
+        }
+        let status = if report.is_expected() { "OK" } else { "FAILED" };
+        println!(" | {:>4} {status}", report.expected);
+    }
+
+    for report in reports {
+        for (i, result) in report.results.iter().enumerate() {
+            if let Some(crash) = result.crash {
+                println!("{} crashed on test {} with {crash}", report.name, i + 1);
+            }
+            if let (Some(comment), true) = (&result.comment, result.verdict != Verdict::AC) {
+                println!(
+                    "{} got {} on test {}: {comment}",
+                    report.name,
+                    result.verdict,
+                    i + 1
+                );
+            }
+        }
+    }
+}
+
+fn print_score_matrix(reports: &[SolutionReport], config: &ProblemConfig) {
+    let width = reports
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Solution".len());
+
+    print!("{:width$}", "Solution");
+    for group in &config.groups {
+        print!(" {:>8}", group.name);
+    }
+    println!(" {:>8} | Expected", "Total");
+
+    for report in reports {
+        print!("{:width$}", report.name);
+        for score in &report.scores {
+            print!(" {score:>8}");
+        }
+        print!(" {:>8}", report.total_score());
+        match report.expected_score {
+            Some(score) => println!(" | {score}"),
+            None => println!(" | -"),
+        }
+    }
+}
+
+fn gen_outputs_command() {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    let generated = generate_outputs(&cpd, &config).unwrap();
+    println!(
+        "Generated {generated} outputs, {} were up to date",
+        config.testcases.len() - generated
+    );
+}
+
+fn gen_tests_command() {
+    let cpd = get_current_problem_directory();
+    let generated = generate_tests(&cpd).unwrap();
+    println!("Generated {generated} tests");
+}
+
+fn stress_command(
+    call: &GeneratorCall,
+    first: &Path,
+    second: &Path,
+    seed: u64,
+    iterations: Option<u64>,
+    save: bool,
+) {
+    let cpd = get_current_problem_directory();
+    let config =
+        ProblemConfig::from_file(File::open(cpd.join("problem_config.json")).unwrap()).unwrap();
+
+    let first = first.file_name().unwrap().to_str().unwrap();
+    let second = second.file_name().unwrap().to_str().unwrap();
+    let Some(counterexample) =
+        stress(&cpd, &config, call, first, second, seed, iterations).unwrap()
+    else {
+        println!("No difference found");
+        return;
+    };
+
+    let call = &counterexample.call;
+    println!("{second} got {} on \"{call}\"", counterexample.verdict);
+    println!("{}", counterexample.message);
+
+    if save {
+        let test = add_test(&cpd, Some(&counterexample.input), false).unwrap();
+        println!("Saved as test {test}");
+    }
+    std::process::exit(1);
 }
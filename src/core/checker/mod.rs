@@ -0,0 +1,199 @@
+pub mod standard;
+
+use super::solution::Verdict;
+use super::source::{Crash, RunOptions};
+use super::{modify_config, GenericResult, ProblemConfig};
+use serde::{Deserialize, Serialize};
+use standard::StandardChecker;
+use std::fmt;
+use std::fs::{read, read_to_string};
+use std::io;
+use std::path::Path;
+
+/// The checker of a problem: either a source file or a standard checker.
+///
+/// Deserializes from the index of the source (3) or the name of the standard checker ("wcmp").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Checker {
+    Source(usize),
+    Standard(StandardChecker),
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Checker::Standard(StandardChecker::Wcmp)
+    }
+}
+
+impl fmt::Display for Checker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Checker::Source(i) => write!(f, "source {i}"),
+            Checker::Standard(checker) => checker.fmt(f),
+        }
+    }
+}
+
+/// The outcome of checking an output, following the testlib exit codes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckerVerdict {
+    /// Exit code 0.
+    Ok,
+    /// Exit code 1.
+    WrongAnswer,
+    /// Exit code 2.
+    PresentationError,
+    /// Exit code 3, the checker itself failed.
+    Fail,
+    /// Exit code 7, the output is worth the given points.
+    Points(f64),
+}
+
+impl CheckerVerdict {
+    /// Interprets the way a checker terminated. `message` is what it wrote to stderr,
+    /// which starts with the points for exit code 7.
+    pub fn from_exit(crash: Option<Crash>, message: &str) -> Self {
+        match crash {
+            None => CheckerVerdict::Ok,
+            Some(Crash::ExitCode(1)) => CheckerVerdict::WrongAnswer,
+            Some(Crash::ExitCode(2)) => CheckerVerdict::PresentationError,
+            Some(Crash::ExitCode(7)) => message
+                .trim_start_matches("points")
+                .split_whitespace()
+                .next()
+                .and_then(|points| points.parse().ok())
+                .map_or(CheckerVerdict::Fail, CheckerVerdict::Points),
+            Some(_) => CheckerVerdict::Fail,
+        }
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match self {
+            CheckerVerdict::Ok | CheckerVerdict::Points(_) => Verdict::AC,
+            CheckerVerdict::WrongAnswer => Verdict::WA,
+            CheckerVerdict::PresentationError => Verdict::PE,
+            CheckerVerdict::Fail => Verdict::FAIL,
+        }
+    }
+}
+
+/// The verdict of a checker along with its message.
+#[derive(Debug, Clone)]
+pub struct CheckerResult {
+    pub verdict: CheckerVerdict,
+    pub message: String,
+}
+
+/// Builds the checker of the problem if it is a source file.
+pub fn build_checker(cpd: &Path, config: &ProblemConfig) -> io::Result<()> {
+    if let Some(Checker::Source(i)) = config.checker {
+        let checker = &config.sources[i];
+        print!(
+            "Building checker {:#?}...",
+            checker.source.file_name().unwrap()
+        );
+        checker.build(cpd)?;
+        println!("Done");
+    }
+    Ok(())
+}
+
+/// Checks `output` against `answer` with the checker of the problem, which is
+/// wcmp if none is set.
+///
+/// A source checker is run with the testlib argument convention `checker <input> <output> <answer>`
+/// and its stderr is kept in `{output}.check`. A checker running past the helper limits fails.
+pub fn check(
+    cpd: &Path,
+    config: &ProblemConfig,
+    input: &Path,
+    output: &Path,
+    answer: &Path,
+) -> io::Result<CheckerResult> {
+    let checker = match config.checker.unwrap_or_default() {
+        Checker::Source(i) => &config.sources[i],
+        Checker::Standard(checker) => return Ok(checker.check(&read(output)?, &read(answer)?)),
+    };
+
+    let log = output.with_extension("check");
+    let options = RunOptions {
+        args: vec![input.into(), output.into(), answer.into()],
+        stdout: Some("/dev/null".into()),
+        stderr: Some(log.clone()),
+        ..Default::default()
+    };
+    let limits = config.helper_limits();
+    let report = checker.run(cpd, &limits, &options)?;
+    if report.time_exceeded(&limits) {
+        return Ok(CheckerResult {
+            verdict: CheckerVerdict::Fail,
+            message: format!(
+                "The checker exceeded the time limit of {:.1}s",
+                limits.time.unwrap().as_secs_f32()
+            ),
+        });
+    }
+    let mut message = read_to_string(&log)?.trim().to_string();
+    let verdict = CheckerVerdict::from_exit(report.crash(), &message);
+
+    if let (true, Some(crash)) = (message.is_empty(), report.crash()) {
+        message = format!("The checker exited with {crash}");
+    }
+
+    Ok(CheckerResult { verdict, message })
+}
+
+/// Sets the checker of the problem to the source file `name`,
+/// or to the standard checker of that name such as "std::wcmp.cpp".
+pub fn set_checker(cpd: &Path, name: &str) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .sources
+            .iter()
+            .position(|x| x.source.file_name().unwrap().eq(name));
+
+        config.checker = match pos {
+            Some(pos) => Some(Checker::Source(pos)),
+            None => Some(Checker::Standard(name.parse().map_err(|_| {
+                format!("{name} was not found in problem_config.json and is not a standard checker")
+            })?)),
+        };
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_testlib_exit_codes() {
+        use CheckerVerdict::*;
+        let exit = |code| Some(Crash::ExitCode(code));
+
+        assert_eq!(CheckerVerdict::from_exit(None, "ok"), Ok);
+        assert_eq!(CheckerVerdict::from_exit(exit(1), ""), WrongAnswer);
+        assert_eq!(CheckerVerdict::from_exit(exit(2), ""), PresentationError);
+        assert_eq!(CheckerVerdict::from_exit(exit(3), ""), Fail);
+        assert_eq!(CheckerVerdict::from_exit(exit(4), ""), Fail);
+        assert_eq!(CheckerVerdict::from_exit(Some(Crash::Signal(11)), ""), Fail);
+    }
+
+    #[test]
+    fn reads_points_from_the_message() {
+        let points = Some(Crash::ExitCode(7));
+        assert_eq!(
+            CheckerVerdict::from_exit(points, "points 0.5 half of the pairs"),
+            CheckerVerdict::Points(0.5)
+        );
+        assert_eq!(
+            CheckerVerdict::from_exit(points, "25"),
+            CheckerVerdict::Points(25.0)
+        );
+        assert_eq!(
+            CheckerVerdict::from_exit(points, "no points"),
+            CheckerVerdict::Fail
+        );
+    }
+}
@@ -0,0 +1,678 @@
+use super::{CheckerResult, CheckerVerdict};
+use bstr::ByteSlice;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The standard testlib checkers, implemented natively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StandardChecker {
+    /// Compares sequences of tokens.
+    Wcmp,
+    /// Compares files line by line, ignoring extra whitespace.
+    Lcmp,
+    /// Compares sequences of signed 64-bit integers.
+    Ncmp,
+    /// Compares sequences of doubles with an absolute or relative error of 1e-4.
+    Rcmp4,
+    /// Compares sequences of doubles with an absolute or relative error of 1e-6.
+    Rcmp6,
+    /// Compares sequences of doubles with an absolute or relative error of 1e-9.
+    Rcmp9,
+    /// Compares a single YES or NO, case insensitive.
+    Yesno,
+    /// Compares sequences of YES or NO, case insensitive.
+    Nyesno,
+    /// Compares files line by line exactly.
+    Fcmp,
+    /// Compares a single arbitrarily big integer.
+    Hcmp,
+}
+
+impl StandardChecker {
+    pub const ALL: [StandardChecker; 10] = [
+        StandardChecker::Wcmp,
+        StandardChecker::Lcmp,
+        StandardChecker::Ncmp,
+        StandardChecker::Rcmp4,
+        StandardChecker::Rcmp6,
+        StandardChecker::Rcmp9,
+        StandardChecker::Yesno,
+        StandardChecker::Nyesno,
+        StandardChecker::Fcmp,
+        StandardChecker::Hcmp,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StandardChecker::Wcmp => "wcmp",
+            StandardChecker::Lcmp => "lcmp",
+            StandardChecker::Ncmp => "ncmp",
+            StandardChecker::Rcmp4 => "rcmp4",
+            StandardChecker::Rcmp6 => "rcmp6",
+            StandardChecker::Rcmp9 => "rcmp9",
+            StandardChecker::Yesno => "yesno",
+            StandardChecker::Nyesno => "nyesno",
+            StandardChecker::Fcmp => "fcmp",
+            StandardChecker::Hcmp => "hcmp",
+        }
+    }
+
+    /// Checks the contestant `output` against the jury `answer`, giving the
+    /// same verdicts and messages as the testlib checker of the same name.
+    pub fn check(&self, output: &[u8], answer: &[u8]) -> CheckerResult {
+        let mut ouf = Stream::new(output, CheckerVerdict::PresentationError);
+        let mut ans = Stream::new(answer, CheckerVerdict::Fail);
+
+        let outcome = match self {
+            StandardChecker::Wcmp => wcmp(&mut ouf, &mut ans),
+            StandardChecker::Lcmp => lcmp(&mut ouf, &mut ans, false),
+            StandardChecker::Fcmp => lcmp(&mut ouf, &mut ans, true),
+            StandardChecker::Ncmp => ncmp(&mut ouf, &mut ans),
+            StandardChecker::Rcmp4 => rcmp(&mut ouf, &mut ans, 4),
+            StandardChecker::Rcmp6 => rcmp(&mut ouf, &mut ans, 6),
+            StandardChecker::Rcmp9 => rcmp(&mut ouf, &mut ans, 9),
+            StandardChecker::Yesno => yesno(&mut ouf, &mut ans),
+            StandardChecker::Nyesno => nyesno(&mut ouf, &mut ans),
+            StandardChecker::Hcmp => hcmp(&mut ouf, &mut ans),
+        };
+
+        // testlib refuses to accept an output with anything left in it.
+        let (verdict, message) = match outcome {
+            Ok(_) if !ouf.seek_eof() => (
+                CheckerVerdict::PresentationError,
+                "Extra information in the output file".to_string(),
+            ),
+            Ok(message) => (CheckerVerdict::Ok, message),
+            Err(quit) => quit,
+        };
+
+        let prefix = match verdict {
+            CheckerVerdict::Ok => "ok",
+            CheckerVerdict::WrongAnswer => "wrong answer",
+            CheckerVerdict::PresentationError => "wrong output format",
+            CheckerVerdict::Fail => "FAIL",
+            CheckerVerdict::Points(_) => "points",
+        };
+
+        CheckerResult {
+            verdict,
+            message: format!("{prefix} {message}"),
+        }
+    }
+}
+
+impl fmt::Display for StandardChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "std::{}", self.name())
+    }
+}
+
+/// Parses the checker name as Polygon writes it ("std::wcmp.cpp") or just "wcmp".
+impl FromStr for StandardChecker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.strip_prefix("std::").unwrap_or(s);
+        let name = name.strip_suffix(".cpp").unwrap_or(name);
+
+        StandardChecker::ALL
+            .into_iter()
+            .find(|checker| checker.name().eq_ignore_ascii_case(name))
+            .ok_or(format!("{s} is not a standard checker"))
+    }
+}
+
+/// `Ok` holds the message of an accepted output, `Err` stops checking with a verdict.
+type Outcome = Result<String, (CheckerVerdict, String)>;
+
+fn quit<T>(verdict: CheckerVerdict, message: String) -> Result<T, (CheckerVerdict, String)> {
+    Err((verdict, message))
+}
+
+/// A file read the way testlib reads its streams. Malformed content
+/// is a presentation error in the output and a failure in the answer.
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+    on_error: CheckerVerdict,
+}
+
+impl<'a> Stream<'a> {
+    fn new(data: &'a [u8], on_error: CheckerVerdict) -> Self {
+        Stream {
+            data,
+            pos: 0,
+            on_error,
+        }
+    }
+
+    fn eof(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Skips whitespace and tells whether the stream is exhausted.
+    fn seek_eof(&mut self) -> bool {
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        self.eof()
+    }
+
+    fn read_token(&mut self, expected: &str) -> Result<&'a [u8], (CheckerVerdict, String)> {
+        if self.seek_eof() {
+            return quit(
+                self.on_error,
+                format!("Unexpected end of file - {expected} expected"),
+            );
+        }
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|c| !c.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        Ok(&self.data[start..self.pos])
+    }
+
+    /// Reads the rest of the current line, without its line break.
+    fn read_line(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let line = match rest.find_byte(b'\n') {
+            Some(end) => {
+                self.pos += end + 1;
+                &rest[..end]
+            }
+            None => {
+                self.pos = self.data.len();
+                rest
+            }
+        };
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+
+    fn read_long(&mut self) -> Result<i64, (CheckerVerdict, String)> {
+        let token = self.read_token("int64")?;
+        match token.to_str().ok().and_then(|t| t.parse().ok()) {
+            Some(value) => Ok(value),
+            None => quit(
+                self.on_error,
+                format!("Expected int64, but \"{}\" found", compress(token)),
+            ),
+        }
+    }
+
+    fn read_double(&mut self) -> Result<f64, (CheckerVerdict, String)> {
+        let token = self.read_token("double")?;
+        let valid = token
+            .iter()
+            .all(|c| c.is_ascii_digit() || b"+-.eE".contains(c));
+        match token
+            .to_str()
+            .ok()
+            .filter(|_| valid)
+            .and_then(|t| t.parse().ok())
+        {
+            Some(value) => Ok(value),
+            None => quit(
+                self.on_error,
+                format!("Expected double, but \"{}\" found", compress(token)),
+            ),
+        }
+    }
+}
+
+/// Shortens long tokens the way testlib does in its messages.
+fn compress(s: &[u8]) -> String {
+    let s = s.to_str_lossy();
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= 64 {
+        return s.into_owned();
+    }
+    let head: String = chars[..30].iter().collect();
+    let tail: String = chars[chars.len() - 31..].iter().collect();
+    format!("{head}...{tail}")
+}
+
+fn english_ending(n: usize) -> &'static str {
+    let n = n % 100;
+    match (n / 10, n % 10) {
+        (1, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    }
+}
+
+fn wcmp(ouf: &mut Stream, ans: &mut Stream) -> Outcome {
+    let mut n = 0;
+    let mut last: &[u8] = b"";
+
+    while !ans.seek_eof() && !ouf.seek_eof() {
+        n += 1;
+        let j = ans.read_token("token")?;
+        let p = ouf.read_token("token")?;
+        if j != p {
+            return quit(
+                CheckerVerdict::WrongAnswer,
+                format!(
+                    "{n}{} words differ - expected: '{}', found: '{}'",
+                    english_ending(n),
+                    compress(j),
+                    compress(p)
+                ),
+            );
+        }
+        last = j;
+    }
+
+    match (ans.seek_eof(), ouf.seek_eof()) {
+        (true, true) if n == 1 => Ok(format!("\"{}\"", compress(last))),
+        (true, true) => Ok(format!("{n} tokens")),
+        (true, false) => quit(
+            CheckerVerdict::WrongAnswer,
+            "Participant output contains extra tokens".to_string(),
+        ),
+        (false, _) => quit(
+            CheckerVerdict::WrongAnswer,
+            "Unexpected EOF in the participants output".to_string(),
+        ),
+    }
+}
+
+/// lcmp compares the words of every line, fcmp compares the lines exactly.
+fn lcmp(ouf: &mut Stream, ans: &mut Stream, exact: bool) -> Outcome {
+    let mut n = 0;
+    let mut last: &[u8] = b"";
+
+    while !ans.eof() {
+        let j = ans.read_line();
+        if j.is_empty() && ans.eof() {
+            break;
+        }
+        let p = ouf.read_line();
+        n += 1;
+
+        let same = match exact {
+            true => j == p,
+            false => j.fields().eq(p.fields()),
+        };
+        if !same {
+            return quit(
+                CheckerVerdict::WrongAnswer,
+                format!(
+                    "{n}{} lines differ - expected: '{}', found: '{}'",
+                    english_ending(n),
+                    compress(j),
+                    compress(p)
+                ),
+            );
+        }
+        last = j;
+    }
+
+    match n {
+        1 => Ok(format!("single line: '{}'", compress(last))),
+        _ => Ok(format!("{n} lines")),
+    }
+}
+
+fn ncmp(ouf: &mut Stream, ans: &mut Stream) -> Outcome {
+    let mut n = 0;
+    let mut first = Vec::new();
+
+    while !ans.seek_eof() && !ouf.seek_eof() {
+        n += 1;
+        let j = ans.read_long()?;
+        let p = ouf.read_long()?;
+        if j != p {
+            return quit(
+                CheckerVerdict::WrongAnswer,
+                format!(
+                    "{n}{} numbers differ - expected: '{j}', found: '{p}'",
+                    english_ending(n)
+                ),
+            );
+        }
+        if n <= 5 {
+            first.push(j.to_string());
+        }
+    }
+
+    let mut extra_in_answer = 0;
+    while !ans.seek_eof() {
+        ans.read_long()?;
+        extra_in_answer += 1;
+    }
+    let mut extra_in_output = 0;
+    while !ouf.seek_eof() {
+        ouf.read_long()?;
+        extra_in_output += 1;
+    }
+    if extra_in_answer > 0 {
+        return quit(
+            CheckerVerdict::WrongAnswer,
+            format!(
+                "Answer contains longer sequence [length = {}], but output contains {n} elements",
+                n + extra_in_answer
+            ),
+        );
+    }
+    if extra_in_output > 0 {
+        return quit(
+            CheckerVerdict::WrongAnswer,
+            format!(
+                "Output contains longer sequence [length = {}], but answer contains {n} elements",
+                n + extra_in_output
+            ),
+        );
+    }
+
+    match n {
+        0..=5 => Ok(format!(
+            "{n} number(s): \"{}\"",
+            compress(first.join(" ").as_bytes())
+        )),
+        _ => Ok(format!("{n} numbers")),
+    }
+}
+
+fn double_compare(expected: f64, result: f64, error: f64) -> bool {
+    let error = error + 1e-15;
+    if expected.is_nan() {
+        result.is_nan()
+    } else if expected.is_infinite() {
+        result == expected
+    } else if result.is_nan() || result.is_infinite() {
+        false
+    } else if (result - expected).abs() <= error {
+        true
+    } else {
+        let min = (expected * (1.0 - error)).min(expected * (1.0 + error));
+        let max = (expected * (1.0 - error)).max(expected * (1.0 + error));
+        min <= result && result <= max
+    }
+}
+
+fn double_delta(expected: f64, result: f64) -> f64 {
+    let absolute = (result - expected).abs();
+    match expected.abs() > 1e-9 {
+        true => absolute.min((absolute / expected).abs()),
+        false => absolute,
+    }
+}
+
+fn rcmp(ouf: &mut Stream, ans: &mut Stream, digits: i32) -> Outcome {
+    let error = 10f64.powi(-digits);
+    let precision = digits as usize + 1;
+    let mut n = 0;
+    let (mut j, mut p) = (0.0, 0.0);
+
+    while !ans.seek_eof() {
+        n += 1;
+        j = ans.read_double()?;
+        p = ouf.read_double()?;
+        if !double_compare(j, p, error) {
+            return quit(
+                CheckerVerdict::WrongAnswer,
+                format!(
+                    "{n}{} numbers differ - expected: '{j:.precision$}', found: '{p:.precision$}', error = '{:.precision$}'",
+                    english_ending(n),
+                    double_delta(j, p)
+                ),
+            );
+        }
+    }
+
+    match n {
+        1 => Ok(format!(
+            "found '{p:.precision$}', expected '{j:.precision$}', error '{:.precision$}'",
+            double_delta(j, p)
+        )),
+        _ => Ok(format!("{n} numbers")),
+    }
+}
+
+fn read_yes_no(stream: &mut Stream) -> Result<String, (CheckerVerdict, String)> {
+    let token = stream
+        .read_token("YES or NO")?
+        .to_str_lossy()
+        .to_uppercase();
+    match token.as_str() {
+        "YES" | "NO" => Ok(token),
+        _ if stream.on_error == CheckerVerdict::Fail => quit(
+            CheckerVerdict::Fail,
+            format!(
+                "YES or NO expected in answer, but {} found",
+                compress(token.as_bytes())
+            ),
+        ),
+        _ => quit(
+            CheckerVerdict::PresentationError,
+            format!(
+                "YES or NO expected, but {} found",
+                compress(token.as_bytes())
+            ),
+        ),
+    }
+}
+
+fn yesno(ouf: &mut Stream, ans: &mut Stream) -> Outcome {
+    let j = read_yes_no(ans)?;
+    let p = read_yes_no(ouf)?;
+    if j != p {
+        return quit(
+            CheckerVerdict::WrongAnswer,
+            format!("expected {j}, found {p}"),
+        );
+    }
+    Ok(format!("answer is {j}"))
+}
+
+fn nyesno(ouf: &mut Stream, ans: &mut Stream) -> Outcome {
+    let mut n = 0;
+    let mut last = String::new();
+
+    while !ans.seek_eof() {
+        let j = read_yes_no(ans)?;
+        let p = read_yes_no(ouf)?;
+        n += 1;
+        if j != p {
+            return quit(
+                CheckerVerdict::WrongAnswer,
+                format!("expected {j}, found {p} [{n}{} token]", english_ending(n)),
+            );
+        }
+        last = j;
+    }
+
+    let mut extra = 0;
+    while !ouf.seek_eof() {
+        ouf.read_token("token")?;
+        extra += 1;
+    }
+    if extra > 0 {
+        return quit(
+            CheckerVerdict::WrongAnswer,
+            format!(
+                "Output contains longer sequence [length = {}], but answer contains {n} elements",
+                n + extra
+            ),
+        );
+    }
+
+    match n {
+        0 => Ok("Empty output".to_string()),
+        1 => Ok(last),
+        _ => Ok(format!("{n} token(s)")),
+    }
+}
+
+fn is_numeric(token: &[u8]) -> bool {
+    let digits = token.strip_prefix(b"-").unwrap_or(token);
+    match digits {
+        b"0" => true,
+        [first, rest @ ..] => (b'1'..=b'9').contains(first) && rest.iter().all(u8::is_ascii_digit),
+        [] => false,
+    }
+}
+
+fn hcmp(ouf: &mut Stream, ans: &mut Stream) -> Outcome {
+    let j = ans.read_token("integer")?;
+    let p = ouf.read_token("integer")?;
+
+    if !is_numeric(j) {
+        return quit(
+            CheckerVerdict::Fail,
+            format!("{} is not a valid integer", compress(j)),
+        );
+    }
+    if !ans.seek_eof() {
+        return quit(
+            CheckerVerdict::Fail,
+            "expected exactly one token in the answer file".to_string(),
+        );
+    }
+    if !is_numeric(p) {
+        return quit(
+            CheckerVerdict::PresentationError,
+            format!("{} is not a valid integer", compress(p)),
+        );
+    }
+    if j != p {
+        return quit(
+            CheckerVerdict::WrongAnswer,
+            format!("expected '{}', found '{}'", compress(j), compress(p)),
+        );
+    }
+    Ok(format!("answer is '{}'", compress(j)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CheckerVerdict::{Fail, Ok as Accepted, PresentationError, WrongAnswer};
+
+    fn check(checker: StandardChecker, output: &str, answer: &str) -> CheckerResult {
+        checker.check(output.as_bytes(), answer.as_bytes())
+    }
+
+    fn verdict(checker: StandardChecker, output: &str, answer: &str) -> CheckerVerdict {
+        check(checker, output, answer).verdict
+    }
+
+    #[test]
+    fn wcmp() {
+        let wcmp = StandardChecker::Wcmp;
+        assert_eq!(check(wcmp, "a  b\n\n", "a b\n").message, "ok 2 tokens");
+        assert_eq!(
+            check(wcmp, "a c", "a b").message,
+            "wrong answer 2nd words differ - expected: 'b', found: 'c'"
+        );
+        assert_eq!(verdict(wcmp, "a", "a b"), WrongAnswer);
+        assert_eq!(verdict(wcmp, "a b c", "a b"), WrongAnswer);
+    }
+
+    #[test]
+    fn lcmp() {
+        let lcmp = StandardChecker::Lcmp;
+        assert_eq!(verdict(lcmp, "1  2\r\n3\n", "1 2\n3\n"), Accepted);
+        assert_eq!(verdict(lcmp, "1\n2 3\n", "1 2\n3\n"), WrongAnswer);
+        assert_eq!(verdict(lcmp, "1 2\n", "1 2\n3\n"), WrongAnswer);
+        assert_eq!(verdict(lcmp, "1 2\n3\n4\n", "1 2\n3\n"), PresentationError);
+    }
+
+    #[test]
+    fn fcmp() {
+        let fcmp = StandardChecker::Fcmp;
+        assert_eq!(verdict(fcmp, "1 2\n3\n", "1 2\n3\n"), Accepted);
+        assert_eq!(verdict(fcmp, "1  2\n3\n", "1 2\n3\n"), WrongAnswer);
+        assert_eq!(verdict(fcmp, "1 2\n", "1 2\n3\n"), WrongAnswer);
+        assert_eq!(verdict(fcmp, "1 2\n3\n4\n", "1 2\n3\n"), PresentationError);
+    }
+
+    #[test]
+    fn ncmp() {
+        let ncmp = StandardChecker::Ncmp;
+        assert_eq!(
+            check(ncmp, "1 -2\n", "1\n-2").message,
+            "ok 2 number(s): \"1 -2\""
+        );
+        assert_eq!(
+            check(ncmp, "1 3", "1 2").message,
+            "wrong answer 2nd numbers differ - expected: '2', found: '3'"
+        );
+        assert_eq!(verdict(ncmp, "1 x", "1 2"), PresentationError);
+        assert_eq!(verdict(ncmp, "1 2", "1 x"), Fail);
+        assert_eq!(
+            check(ncmp, "1", "1 2 3").message,
+            "wrong answer Answer contains longer sequence [length = 3], but output contains 1 elements"
+        );
+        assert_eq!(
+            check(ncmp, "1 2 3", "1").message,
+            "wrong answer Output contains longer sequence [length = 3], but answer contains 1 elements"
+        );
+    }
+
+    #[test]
+    fn rcmp() {
+        let rcmp6 = StandardChecker::Rcmp6;
+        assert_eq!(verdict(rcmp6, "1.0000001 2e3", "1 2000"), Accepted);
+        assert_eq!(verdict(rcmp6, "1.00001 2000", "1 2000"), WrongAnswer);
+        assert_eq!(verdict(rcmp6, "1 abc", "1 2000"), PresentationError);
+        assert_eq!(verdict(rcmp6, "1", "1 2000"), PresentationError);
+        assert_eq!(verdict(rcmp6, "1 2000 3", "1 2000"), PresentationError);
+        assert_eq!(verdict(StandardChecker::Rcmp4, "1.00001", "1"), Accepted);
+        assert_eq!(verdict(StandardChecker::Rcmp9, "1.00001", "1"), WrongAnswer);
+    }
+
+    #[test]
+    fn yesno() {
+        let yesno = StandardChecker::Yesno;
+        assert_eq!(check(yesno, "yes\n", "YES\n").message, "ok answer is YES");
+        assert_eq!(verdict(yesno, "NO", "YES"), WrongAnswer);
+        assert_eq!(verdict(yesno, "maybe", "YES"), PresentationError);
+        assert_eq!(verdict(yesno, "", "YES"), PresentationError);
+        assert_eq!(verdict(yesno, "YES YES", "YES"), PresentationError);
+    }
+
+    #[test]
+    fn nyesno() {
+        let nyesno = StandardChecker::Nyesno;
+        assert_eq!(verdict(nyesno, "yes No", "YES NO"), Accepted);
+        assert_eq!(verdict(nyesno, "YES YES", "YES NO"), WrongAnswer);
+        assert_eq!(verdict(nyesno, "YES maybe", "YES NO"), PresentationError);
+        assert_eq!(verdict(nyesno, "YES", "YES NO"), PresentationError);
+        assert_eq!(verdict(nyesno, "YES NO YES", "YES NO"), WrongAnswer);
+    }
+
+    #[test]
+    fn hcmp() {
+        let hcmp = StandardChecker::Hcmp;
+        let big = "-123456789012345678901234567890";
+        assert_eq!(verdict(hcmp, big, big), Accepted);
+        assert_eq!(verdict(hcmp, "-1", big), WrongAnswer);
+        assert_eq!(verdict(hcmp, "-0", "-0"), Accepted);
+        assert_eq!(verdict(hcmp, "-0", "0"), WrongAnswer);
+        assert_eq!(verdict(hcmp, "-012", "-12"), PresentationError);
+        assert_eq!(verdict(hcmp, "012", "12"), PresentationError);
+        assert_eq!(verdict(hcmp, "", "12"), PresentationError);
+        assert_eq!(verdict(hcmp, "12 13", "12"), PresentationError);
+        assert_eq!(verdict(hcmp, "12", "+12"), Fail);
+    }
+
+    #[test]
+    fn parses_polygon_names() {
+        assert_eq!("std::ncmp.cpp".parse(), Ok(StandardChecker::Ncmp));
+        assert_eq!("RCMP6".parse(), Ok(StandardChecker::Rcmp6));
+        assert!("std::foo.cpp".parse::<StandardChecker>().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use super::language::{Language, Toolchain};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env::var_os;
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// The name of the file in a problem directory overriding the user config.
+pub const PROBLEM_CONFIG_FILE: &str = "rustygon_config.json";
+
+/// The settings of rustygon, read from the user config and the problem override.
+///
+/// ```json
+/// {
+///   "profiles": {
+///     "cpp20": {
+///       "compiler": "g++",
+///       "compiler_args": ["-O2", "-std=c++20", "-DONLINE_JUDGE", "%source%", "-o", "%bin%"]
+///     }
+///   },
+///   "extensions": { "cpp": "cpp20" }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Toolchains by name. A profile named like a language replaces its built-in toolchain.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Toolchain>,
+    /// The profile or language used for the sources with an extension, such as "cpp": "cpp20".
+    #[serde(default)]
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        serde_json::from_reader(File::open(path)?)
+            .map_err(|e| format!("Invalid config {}: {e}", path.display()).into())
+    }
+
+    /// The user config: "$XDG_CONFIG_HOME/rustygon/config.json",
+    /// or "~/.config/rustygon/config.json".
+    pub fn user_path() -> Option<PathBuf> {
+        let dir = var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(dir.join("rustygon").join("config.json"))
+    }
+
+    /// Returns this config with the overrides of the problem at `cpd` applied.
+    pub fn with_problem(&self, cpd: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = cpd.join(PROBLEM_CONFIG_FILE);
+        let mut config = self.clone();
+        if !path.exists() {
+            return Ok(config);
+        }
+
+        let problem = Config::load(&path)?;
+        config.profiles.extend(problem.profiles);
+        config.extensions.extend(problem.extensions);
+        Ok(config)
+    }
+
+    /// Finds the toolchain of a profile, or the built-in toolchain of a language, by name.
+    pub fn toolchain(&self, name: &str) -> Option<Toolchain> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| Language::from_name(name).map(|language| language.toolchain()))
+    }
+
+    /// Finds the toolchain of a source from its extension.
+    pub fn toolchain_for(&self, path: &Path) -> Option<Toolchain> {
+        let extension = path.extension()?.to_str()?;
+        match self.extensions.get(extension) {
+            Some(name) => self.toolchain(name),
+            None => self.toolchain(Language::from_path(path)?.name()),
+        }
+    }
+}
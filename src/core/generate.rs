@@ -0,0 +1,375 @@
+use super::checker::CheckerVerdict;
+use super::interactor::{build_interactor, interact};
+use super::source::{more_recent, RunOptions};
+use super::testcase::{testcase_paths, Testcase};
+use super::validate::ensure_valid;
+use super::{modify_config, GenericResult, ProblemConfig};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs::{create_dir_all, remove_file, rename};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A line of the generation script: runs a generator from the sources with
+/// some arguments and uses its output as the input of a testcase.
+///
+/// Written as "gen 10 20 --seed 3 > 5", where the target is either a test
+/// number or "$" for the first free test number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorCall {
+    /// The file name of the generator source, with or without its extension.
+    pub generator: String,
+    pub args: Vec<String>,
+    /// The 1-based number of the generated test, or `None` for the first free one.
+    pub test: Option<usize>,
+}
+
+impl FromStr for GeneratorCall {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (command, target) = s
+            .rsplit_once('>')
+            .ok_or(format!("Missing '> test' in \"{s}\""))?;
+
+        let test = match target.trim() {
+            "$" => None,
+            n => Some(
+                n.parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(format!("Invalid test number \"{n}\""))?,
+            ),
+        };
+
+        let mut words = command.split_whitespace().map(str::to_string);
+        let generator = words
+            .next()
+            .ok_or(format!("Missing generator in \"{s}\""))?;
+
+        Ok(GeneratorCall {
+            generator,
+            args: words.collect(),
+            test,
+        })
+    }
+}
+
+impl fmt::Display for GeneratorCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.generator)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        match self.test {
+            Some(test) => write!(f, " > {test}"),
+            None => write!(f, " > $"),
+        }
+    }
+}
+
+/// Finds the source of a generator by its file name, with or without its extension.
+pub fn find_generator(config: &ProblemConfig, name: &str) -> Option<usize> {
+    config.sources.iter().position(|source| {
+        source.source.file_name().is_some_and(|n| n == name)
+            || source.source.file_stem().is_some_and(|n| n == name)
+    })
+}
+
+/// Appends `call` to the generation script of the problem.
+pub fn add_script_line(cpd: &Path, call: GeneratorCall) -> GenericResult {
+    modify_config(cpd, |config| {
+        if find_generator(config, &call.generator).is_none() {
+            return Err(format!("{} was not found in problem_config.json", call.generator).into());
+        }
+        config.script.push(call.clone());
+        Ok(())
+    })
+}
+
+/// Replaces the generation script of the problem with `script`.
+pub fn set_script(cpd: &Path, script: Vec<GeneratorCall>) -> GenericResult {
+    modify_config(cpd, |config| {
+        if let Some(call) = script
+            .iter()
+            .find(|call| find_generator(config, &call.generator).is_none())
+        {
+            return Err(format!("{} was not found in problem_config.json", call.generator).into());
+        }
+        config.script = script.clone();
+        Ok(())
+    })
+}
+
+/// Parses a generation script with one generator call per line.
+/// Empty lines and lines starting with '#' are ignored.
+pub fn parse_script(script: &str) -> Result<Vec<GeneratorCall>, String> {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(GeneratorCall::from_str)
+        .collect()
+}
+
+/// What a test number is used for while laying out the generated testcases.
+enum Slot {
+    Manual(Testcase),
+    /// Generated by the script line with this index.
+    Script(usize),
+    Free,
+}
+
+/// Materialises every testcase of the generation script.
+///
+/// Generated testcases are replaced by the ones the script currently describes,
+/// while manually added testcases keep their numbers. Calls with an explicit test
+/// number are placed first, then the "$" calls fill the free numbers in order.
+/// Returns the number of generated testcases.
+pub fn generate_tests(cpd: &Path) -> Result<usize, Box<dyn Error>> {
+    let mut generated = 0;
+    modify_config(cpd, |config| {
+        let old_len = config.testcases.len();
+        let samples: Vec<bool> = config.testcases.iter().map(|t| t.sample).collect();
+        let groups: Vec<Option<String>> =
+            config.testcases.iter().map(|t| t.group.clone()).collect();
+        let mut slots: Vec<Slot> = config
+            .testcases
+            .drain(..)
+            .map(|testcase| match testcase.generate {
+                true => Slot::Free,
+                false => Slot::Manual(testcase),
+            })
+            .collect();
+
+        let (explicit, free): (Vec<_>, Vec<_>) =
+            (0..config.script.len()).partition(|&i| config.script[i].test.is_some());
+
+        for i in explicit {
+            let n = config.script[i].test.unwrap();
+            if slots.len() < n {
+                slots.resize_with(n, || Slot::Free);
+            }
+            if !matches!(slots[n - 1], Slot::Free) {
+                return Err(format!("Test {n} is already taken: {}", config.script[i]).into());
+            }
+            slots[n - 1] = Slot::Script(i);
+        }
+        for i in free {
+            match slots.iter().position(|slot| matches!(slot, Slot::Free)) {
+                Some(pos) => slots[pos] = Slot::Script(i),
+                None => slots.push(Slot::Script(i)),
+            }
+        }
+
+        while matches!(slots.last(), Some(Slot::Free)) {
+            slots.pop();
+        }
+        if let Some(hole) = slots.iter().position(|slot| matches!(slot, Slot::Free)) {
+            return Err(
+                format!("Test {} is neither added manually nor generated", hole + 1).into(),
+            );
+        }
+
+        for n in slots.len() + 1..=old_len {
+            let (input_path, output_path) = testcase_paths(cpd, n);
+            remove_file(input_path)?;
+            if output_path.exists() {
+                remove_file(output_path)?;
+            }
+        }
+
+        let limits = config.helper_limits();
+        let mut generators = Vec::with_capacity(config.script.len());
+        for call in &config.script {
+            let index = find_generator(config, &call.generator).ok_or(format!(
+                "{} was not found in problem_config.json",
+                call.generator
+            ))?;
+            if !generators.contains(&index) {
+                let generator = &config.sources[index];
+                print!("Building {:#?}...", generator.source.file_name().unwrap());
+                generator.build(cpd)?;
+                println!("Done");
+            }
+            generators.push(index);
+        }
+
+        for (n, slot) in slots.into_iter().enumerate().map(|(i, slot)| (i + 1, slot)) {
+            let i = match slot {
+                Slot::Manual(testcase) => {
+                    config.testcases.push(testcase);
+                    continue;
+                }
+                Slot::Script(i) => i,
+                Slot::Free => unreachable!(),
+            };
+
+            let call = &config.script[i];
+            let (input_path, output_path) = testcase_paths(cpd, n);
+            let options = RunOptions {
+                args: call.args.iter().map(Into::into).collect(),
+                stdout: Some(input_path.clone()),
+                ..Default::default()
+            };
+            let report = config.sources[generators[i]].run(cpd, &limits, &options)?;
+            if report.time_exceeded(&limits) {
+                return Err(format!(
+                    "{call} exceeded the time limit of {:.1}s",
+                    limits.time.unwrap().as_secs_f32()
+                )
+                .into());
+            }
+            if let Some(crash) = report.crash() {
+                return Err(format!("{call} crashed with {crash}").into());
+            }
+
+            config.testcases.push(Testcase {
+                input_path,
+                output_path,
+                generate: true,
+                sample: samples.get(n - 1).copied().unwrap_or(false),
+                group: groups.get(n - 1).cloned().flatten(),
+            });
+        }
+
+        generated = config.script.len();
+        Ok(())
+    })?;
+    Ok(generated)
+}
+
+/// Runs the main solution on the input of every testcase to write its output.
+///
+/// Outputs that are more recent than both their input and the main solution binary
+/// are left untouched. Returns the number of outputs that were (re)written.
+/// Nothing is generated while the validator rejects any of the inputs.
+/// For an interactive problem the output is the one the interactor writes.
+pub fn generate_outputs(cpd: &Path, config: &ProblemConfig) -> Result<usize, Box<dyn Error>> {
+    ensure_valid(cpd, config)?;
+
+    let main = config
+        .main_solution
+        .map(|i| &config.solutions[i].sourcefile)
+        .ok_or("The problem has no main solution")?;
+
+    print!("Building {:#?}...", main.source.file_name().unwrap());
+    main.build(cpd)?;
+    println!("Done");
+    build_interactor(cpd, config)?;
+
+    let bin_path = main.bin_path(cpd);
+    let limits = config.limits();
+    let logs = cpd.join("bin").join("interaction");
+    let mut generated = 0;
+
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        let output = &testcase.output_path;
+        if !more_recent(&testcase.input_path, output)? && !more_recent(&bin_path, output)? {
+            continue;
+        }
+
+        // The output is written aside and only moved in place once the main solution
+        // succeeded, so a failed run never leaves an output that looks up to date.
+        let partial = output.with_extension("partial");
+        let run = if config.interactive {
+            create_dir_all(&logs)?;
+            let log = logs.join(format!("{:02}", i + 1));
+            interact(cpd, config, main, &testcase.input_path, &partial, &log)
+                .map(|interaction| (interaction.solution, Some(interaction.result)))
+        } else {
+            let options = RunOptions {
+                stdin: Some(testcase.input_path.clone()),
+                stdout: Some(partial.clone()),
+                ..Default::default()
+            };
+            main.run(cpd, &limits, &options)
+                .map(|report| (report, None))
+        };
+
+        let error = match run {
+            Err(error) => Some(error.to_string()),
+            Ok((report, interaction)) => {
+                if let Some(verdict) = report.limit_verdict(&limits) {
+                    Some(format!("The main solution got {verdict} on test {}", i + 1))
+                } else if let Some(crash) = report.crash() {
+                    Some(format!(
+                        "The main solution crashed on test {} with {crash}",
+                        i + 1
+                    ))
+                } else {
+                    interaction
+                        .filter(|r| r.verdict != CheckerVerdict::Ok)
+                        .map(|result| {
+                            format!(
+                                "The interactor rejected the main solution on test {}: {}",
+                                i + 1,
+                                result.message
+                            )
+                        })
+                }
+            }
+        };
+
+        if let Some(error) = error {
+            if partial.exists() {
+                remove_file(&partial)?;
+            }
+            return Err(error.into());
+        }
+        rename(&partial, output)?;
+        generated += 1;
+    }
+
+    Ok(generated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_generator_calls() {
+        assert_eq!(
+            "gen 10 20 --seed 3 > 5".parse(),
+            Ok(GeneratorCall {
+                generator: "gen".to_string(),
+                args: vec!["10", "20", "--seed", "3"]
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                test: Some(5),
+            })
+        );
+        assert_eq!(
+            "  gen.cpp   >   $ ".parse(),
+            Ok(GeneratorCall {
+                generator: "gen.cpp".to_string(),
+                args: Vec::new(),
+                test: None,
+            })
+        );
+        // Only the last '>' separates the target.
+        assert_eq!(
+            "gen a>b > 2".parse::<GeneratorCall>().unwrap().args,
+            ["a>b"]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_calls() {
+        assert!("gen 1 2".parse::<GeneratorCall>().is_err());
+        assert!("gen > 0".parse::<GeneratorCall>().is_err());
+        assert!("gen > x".parse::<GeneratorCall>().is_err());
+        assert!(" > 3".parse::<GeneratorCall>().is_err());
+    }
+
+    #[test]
+    fn displays_as_parsed() {
+        for line in ["gen 10 20 > 5", "gen > $"] {
+            let call: GeneratorCall = line.parse().unwrap();
+            assert_eq!(call.to_string(), line);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use super::solution::Verdict;
+use super::{modify_config, GenericResult, ProblemConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A named group of testcases worth some points, as in IOI-style subtasks.
+///
+/// A solution gets the points of a group only if it passes every test of the group
+/// and gets the full points of every group the group depends on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestGroup {
+    pub name: String,
+    pub points: f64,
+    /// The names of the groups that must be passed for this group to score.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+/// Adds a group worth `points` to the problem.
+/// The groups in `dependencies` must already exist.
+pub fn add_group(cpd: &Path, name: &str, points: f64, dependencies: &[String]) -> GenericResult {
+    modify_config(cpd, |config| {
+        if config.groups.iter().any(|group| group.name == name) {
+            return Err(format!("Group {name} already exists").into());
+        }
+        if let Some(missing) = dependencies
+            .iter()
+            .find(|dependency| !config.groups.iter().any(|group| &group.name == *dependency))
+        {
+            return Err(format!("Group {missing} does not exist").into());
+        }
+
+        config.groups.push(TestGroup {
+            name: name.to_string(),
+            points,
+            dependencies: dependencies.to_vec(),
+        });
+        Ok(())
+    })
+}
+
+/// Removes a group that no other group depends on.
+/// Its testcases are left without a group.
+pub fn remove_group(cpd: &Path, name: &str) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .groups
+            .iter()
+            .position(|group| group.name == name)
+            .ok_or(format!("Group {name} does not exist"))?;
+
+        if let Some(dependent) = config
+            .groups
+            .iter()
+            .find(|group| group.dependencies.iter().any(|d| d == name))
+        {
+            return Err(format!("Group {} depends on {name}", dependent.name).into());
+        }
+
+        config.groups.remove(pos);
+        for testcase in &mut config.testcases {
+            if testcase.group.as_deref() == Some(name) {
+                testcase.group = None;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Puts the testcases `tests` (1-based) in the group `name`.
+pub fn set_group(cpd: &Path, name: &str, tests: &[usize]) -> GenericResult {
+    modify_config(cpd, |config| {
+        if !config.groups.iter().any(|group| group.name == name) {
+            return Err(format!("Group {name} does not exist").into());
+        }
+
+        for &n in tests {
+            let testcase = n
+                .checked_sub(1)
+                .and_then(|i| config.testcases.get_mut(i))
+                .ok_or(format!("Test {n} does not exist"))?;
+            testcase.group = Some(name.to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Checks that every dependency of `groups` is another group and that no group
+/// depends on itself, directly or through other groups.
+pub fn check_dependencies(groups: &[TestGroup]) -> Result<(), String> {
+    for group in groups {
+        if let Some(missing) = group
+            .dependencies
+            .iter()
+            .find(|dependency| !groups.iter().any(|g| &g.name == *dependency))
+        {
+            return Err(format!(
+                "Group {} depends on {missing}, which does not exist",
+                group.name
+            ));
+        }
+    }
+
+    let order = dependency_order(groups);
+    match groups
+        .iter()
+        .find(|group| !order.iter().any(|g| g.name == group.name))
+    {
+        Some(group) => Err(format!("Group {} depends on itself", group.name)),
+        None => Ok(()),
+    }
+}
+
+/// Orders the groups so that every group comes after the groups it depends on.
+/// Groups depending on themselves, directly or through other groups, are left out.
+fn dependency_order(groups: &[TestGroup]) -> Vec<&TestGroup> {
+    let mut order: Vec<&TestGroup> = Vec::with_capacity(groups.len());
+    loop {
+        let len = order.len();
+        for group in groups {
+            let placed = |name: &str| order.iter().any(|g| g.name == name);
+            let ready = group.dependencies.iter().all(|dependency| {
+                placed(dependency) || !groups.iter().any(|g| &g.name == dependency)
+            });
+            if ready && !placed(&group.name) {
+                order.push(group);
+            }
+        }
+        if order.len() == len {
+            return order;
+        }
+    }
+}
+
+/// Returns the points a solution gets for each group of the problem, given its
+/// verdict on every testcase. Testcases outside of any group are not scored.
+/// A group with a missing dependency or depending on itself scores nothing.
+pub fn group_scores(config: &ProblemConfig, verdicts: &[Verdict]) -> Vec<f64> {
+    let mut passed: HashMap<&str, bool> = HashMap::new();
+
+    for group in dependency_order(&config.groups) {
+        let tests_passed = config
+            .testcases
+            .iter()
+            .zip(verdicts)
+            .filter(|(testcase, _)| testcase.group.as_ref() == Some(&group.name))
+            .all(|(_, verdict)| *verdict == Verdict::AC);
+
+        let dependencies_passed = group
+            .dependencies
+            .iter()
+            .all(|dependency| passed.get(dependency.as_str()) == Some(&true));
+
+        passed.insert(&group.name, tests_passed && dependencies_passed);
+    }
+
+    config
+        .groups
+        .iter()
+        .map(|group| match passed.get(group.name.as_str()) {
+            Some(true) => group.points,
+            _ => 0.0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use Verdict::{AC, WA};
+
+    /// A problem with one testcase per entry of `tests`, holding the name of its group.
+    fn problem(groups: &[(&str, f64, &[&str])], tests: &[&str]) -> ProblemConfig {
+        let groups: Vec<_> = groups
+            .iter()
+            .map(|(name, points, dependencies)| {
+                json!({ "name": name, "points": points, "dependencies": dependencies })
+            })
+            .collect();
+        let testcases: Vec<_> = tests
+            .iter()
+            .map(|group| {
+                json!({
+                    "input_path": "",
+                    "output_path": "",
+                    "generate": false,
+                    "sample": false,
+                    "group": group,
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "title": "",
+            "time": 1.0,
+            "tags": [],
+            "testcases": testcases,
+            "groups": groups,
+            "sources": [],
+            "solutions": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn scores_passed_groups() {
+        let config = problem(&[("a", 30.0, &[]), ("b", 70.0, &[])], &["a", "b", "b"]);
+        assert_eq!(group_scores(&config, &[AC, AC, AC]), [30.0, 70.0]);
+        assert_eq!(group_scores(&config, &[AC, AC, WA]), [30.0, 0.0]);
+        assert_eq!(group_scores(&config, &[WA, AC, AC]), [0.0, 70.0]);
+    }
+
+    #[test]
+    fn failed_dependency_scores_nothing() {
+        let config = problem(&[("a", 30.0, &[]), ("b", 70.0, &["a"])], &["a", "b"]);
+        assert_eq!(group_scores(&config, &[WA, AC]), [0.0, 0.0]);
+        assert_eq!(group_scores(&config, &[AC, AC]), [30.0, 70.0]);
+    }
+
+    #[test]
+    fn resolves_forward_dependencies() {
+        let config = problem(&[("b", 70.0, &["a"]), ("a", 30.0, &[])], &["a", "b"]);
+        assert_eq!(group_scores(&config, &[AC, AC]), [70.0, 30.0]);
+        assert_eq!(group_scores(&config, &[WA, AC]), [0.0, 0.0]);
+        assert_eq!(check_dependencies(&config.groups), Ok(()));
+    }
+
+    #[test]
+    fn cycles_score_nothing() {
+        let config = problem(&[("a", 30.0, &["a"]), ("b", 70.0, &[])], &["a", "b"]);
+        assert_eq!(group_scores(&config, &[AC, AC]), [0.0, 70.0]);
+        assert!(check_dependencies(&config.groups).is_err());
+
+        let config = problem(&[("a", 30.0, &["b"]), ("b", 70.0, &["a"])], &["a", "b"]);
+        assert_eq!(group_scores(&config, &[AC, AC]), [0.0, 0.0]);
+        assert!(check_dependencies(&config.groups).is_err());
+    }
+
+    #[test]
+    fn missing_dependency_scores_nothing() {
+        let config = problem(&[("a", 30.0, &["c"])], &["a"]);
+        assert_eq!(group_scores(&config, &[AC]), [0.0]);
+        assert!(check_dependencies(&config.groups).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use super::checker::{CheckerResult, CheckerVerdict};
+use super::source::{wait_child, Limits, RunReport, SourceFile};
+use super::{modify_config, GenericResult, ProblemConfig};
+use std::fs::{read_to_string, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::thread;
+
+/// How a solution went when run against the interactor.
+#[derive(Debug, Clone)]
+pub struct Interaction {
+    pub solution: RunReport,
+    pub interactor: RunReport,
+    /// The verdict of the interactor, following the testlib exit codes.
+    pub result: CheckerResult,
+}
+
+/// Returns the interactor of an interactive problem.
+pub fn interactor(config: &ProblemConfig) -> io::Result<&SourceFile> {
+    config
+        .interactor
+        .and_then(|i| config.sources.get(i))
+        .ok_or(io::Error::other(
+            "The problem is interactive but has no interactor",
+        ))
+}
+
+/// Builds the interactor of the problem if it is interactive.
+pub fn build_interactor(cpd: &Path, config: &ProblemConfig) -> io::Result<()> {
+    if config.interactive {
+        let interactor = interactor(config)?;
+        print!(
+            "Building interactor {:#?}...",
+            interactor.source.file_name().unwrap()
+        );
+        interactor.build(cpd)?;
+        println!("Done");
+    }
+    Ok(())
+}
+
+/// Runs `solution` with its standard input and output connected to the interactor.
+///
+/// The interactor is run as `interactor <input> <output>`, where it reads the test
+/// from `input` and writes the result of the interaction to `output` for the checker.
+/// Both processes run under the limits of the problem. Next to `logs` are kept
+/// the standard error of the solution in `{logs}.err`, the message of the interactor
+/// in `{logs}.interactor` and the transcript of the interaction in `{logs}.log`,
+/// where the lines written by the solution start with "> " and the lines written
+/// by the interactor start with "< ".
+pub fn interact(
+    cpd: &Path,
+    config: &ProblemConfig,
+    solution: &SourceFile,
+    input: &Path,
+    output: &Path,
+    logs: &Path,
+) -> io::Result<Interaction> {
+    let interactor = interactor(config)?;
+    let limits = config.limits();
+    // The interactor spends most of its time waiting for the solution, so its wall
+    // time guard must not fire before the one of the solution.
+    let interactor_limits = Limits {
+        time: limits.time.map(|time| time * 2),
+        ..limits
+    };
+    let message = logs.with_extension("interactor");
+
+    let mut interactor_command = interactor.command(cpd, &interactor_limits)?;
+    interactor_command
+        .args([input, output])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(File::create(&message)?);
+
+    let mut solution_command = solution.command(cpd, &limits)?;
+    solution_command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(File::create(logs.with_extension("err"))?);
+
+    let mut interactor_child = interactor_command.spawn()?;
+    let mut solution_child = match solution_command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            interactor_child.kill()?;
+            interactor_child.wait()?;
+            return Err(error);
+        }
+    };
+
+    let to_interactor = interactor_child.stdin.take().unwrap();
+    let from_interactor = interactor_child.stdout.take().unwrap();
+    let to_solution = solution_child.stdin.take().unwrap();
+    let from_solution = solution_child.stdout.take().unwrap();
+
+    let transcript = Mutex::new(File::create(logs.with_extension("log"))?);
+    let transcript = &transcript;
+
+    let (solution_report, interactor_report) = thread::scope(|scope| {
+        let forward = scope.spawn(move || pipe(from_solution, to_interactor, transcript, "> "));
+        let backward = scope.spawn(move || pipe(from_interactor, to_solution, transcript, "< "));
+        let solution = scope.spawn(move || wait_child(solution_child, &limits));
+
+        let interactor_report = wait_child(interactor_child, &interactor_limits);
+        let solution_report = solution.join().unwrap();
+        forward.join().unwrap()?;
+        backward.join().unwrap()?;
+        io::Result::Ok((solution_report?, interactor_report?))
+    })?;
+
+    let mut message = read_to_string(&message)?.trim().to_string();
+    let verdict = if interactor_report
+        .limit_verdict(&interactor_limits)
+        .is_some()
+    {
+        message = "The interactor exceeded the limits".to_string();
+        CheckerVerdict::Fail
+    } else {
+        CheckerVerdict::from_exit(interactor_report.crash(), &message)
+    };
+
+    if let (true, Some(crash)) = (message.is_empty(), interactor_report.crash()) {
+        message = format!("The interactor exited with {crash}");
+    }
+
+    Ok(Interaction {
+        solution: solution_report,
+        interactor: interactor_report,
+        result: CheckerResult { verdict, message },
+    })
+}
+
+/// Forwards everything read from `from` to `to` until either end is closed,
+/// logging it to `transcript` with every line starting with `prefix`.
+/// A line is logged once it is complete, so reads splitting a line do not split it in the log.
+fn pipe(
+    mut from: impl Read,
+    mut to: impl Write,
+    transcript: &Mutex<File>,
+    prefix: &str,
+) -> io::Result<()> {
+    let mut buffer = [0; 4096];
+    // What was read after the last newline.
+    let mut pending = Vec::new();
+    loop {
+        let read = match from.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            // A broken stream is handled like a closed one.
+            Err(_) => break,
+        };
+        if to
+            .write_all(&buffer[..read])
+            .and_then(|_| to.flush())
+            .is_err()
+        {
+            break;
+        }
+
+        pending.extend_from_slice(&buffer[..read]);
+        if let Some(end) = pending.iter().rposition(|&c| c == b'\n') {
+            log_lines(transcript, prefix, &pending[..=end])?;
+            pending.drain(..=end);
+        }
+    }
+
+    if !pending.is_empty() {
+        pending.push(b'\n');
+        log_lines(transcript, prefix, &pending)?;
+    }
+    Ok(())
+}
+
+/// Writes newline terminated `lines` to `transcript`, each starting with `prefix`.
+fn log_lines(transcript: &Mutex<File>, prefix: &str, lines: &[u8]) -> io::Result<()> {
+    let mut transcript = transcript.lock().unwrap();
+    for line in lines.split_inclusive(|&c| c == b'\n') {
+        transcript.write_all(prefix.as_bytes())?;
+        transcript.write_all(line)?;
+    }
+    Ok(())
+}
+
+/// Marks the problem as interactive or not.
+pub fn set_interactive(cpd: &Path, interactive: bool) -> GenericResult {
+    modify_config(cpd, |config| {
+        config.interactive = interactive;
+        Ok(())
+    })
+}
+
+/// Sets the interactor of the problem to the source file `name`,
+/// which also marks the problem as interactive.
+pub fn set_interactor(cpd: &Path, name: &str) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .sources
+            .iter()
+            .position(|x| x.source.file_name().unwrap().eq(name))
+            .ok_or(format!("{name} was not found in problem_config.json"))?;
+
+        config.interactor = Some(pos);
+        config.interactive = true;
+        Ok(())
+    })
+}
@@ -0,0 +1,185 @@
+use super::checker::{build_checker, check, CheckerResult, CheckerVerdict};
+use super::group::group_scores;
+use super::interactor::{build_interactor, interact};
+use super::solution::{ExpectedVerdict, Solution, Verdict};
+use super::source::{Crash, Limits, RunOptions, SourceFile};
+use super::testcase::Testcase;
+use super::ProblemConfig;
+use std::error::Error;
+use std::fs::create_dir_all;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// The outcome of running a single solution on a single testcase.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub verdict: Verdict,
+    pub time: Duration,
+    /// How the solution crashed, for a RE verdict.
+    pub crash: Option<Crash>,
+    /// The message of the checker, if it was run.
+    pub comment: Option<String>,
+    /// The points given by the checker, if it gave any.
+    pub points: Option<f64>,
+}
+
+impl TestResult {
+    fn set_checker_result(&mut self, checked: CheckerResult) {
+        self.verdict = checked.verdict.verdict();
+        self.comment = Some(checked.message);
+        if let CheckerVerdict::Points(points) = checked.verdict {
+            self.points = Some(points);
+        }
+    }
+}
+
+/// The results of a solution on every testcase of the problem, in order.
+#[derive(Debug)]
+pub struct SolutionReport {
+    pub name: String,
+    pub expected: ExpectedVerdict,
+    pub results: Vec<TestResult>,
+    /// The points scored in each test group of the problem, in order.
+    pub scores: Vec<f64>,
+    /// The total points the solution is expected to score, if declared.
+    pub expected_score: Option<f64>,
+}
+
+impl SolutionReport {
+    pub fn verdicts(&self) -> Vec<Verdict> {
+        self.results.iter().map(|r| r.verdict).collect()
+    }
+
+    pub fn total_score(&self) -> f64 {
+        self.scores.iter().sum()
+    }
+
+    /// Whether the total score is the expected one, if the solution declares any.
+    pub fn score_matches(&self) -> bool {
+        self.expected_score
+            .is_none_or(|score| (score - self.total_score()).abs() < 1e-6)
+    }
+
+    /// Whether the observed verdicts and score agree with the declared ones of the solution.
+    pub fn is_expected(&self) -> bool {
+        self.expected.accepts(&self.verdicts()) && self.score_matches()
+    }
+}
+
+/// Builds every solution (and the checker, if any) and runs each solution on every testcase.
+///
+/// The output of each run is stored in "{cpd}/bin/invocation/{solution}/{test}"
+/// and its standard error in "{cpd}/bin/invocation/{solution}/{test}.err".
+/// For an interactive problem the output is the one written by the interactor.
+/// Every testcase must have its output.
+pub fn invoke(cpd: &Path, config: &ProblemConfig) -> Result<Vec<SolutionReport>, Box<dyn Error>> {
+    if let Some(n) = config
+        .testcases
+        .iter()
+        .position(|testcase| !testcase.output_path.exists())
+    {
+        return Err(format!("Test {} has no output. Generate the outputs first", n + 1).into());
+    }
+    build_checker(cpd, config)?;
+    build_interactor(cpd, config)?;
+
+    let limits = config.limits();
+    let mut reports = Vec::with_capacity(config.solutions.len());
+
+    for solution in &config.solutions {
+        let name = solution_name(solution);
+        print!("Building {name:#?}...");
+        solution.sourcefile.build(cpd)?;
+        println!("Done");
+
+        let output_dir = cpd.join("bin").join("invocation").join(&name);
+        create_dir_all(&output_dir)?;
+
+        let mut results = Vec::with_capacity(config.testcases.len());
+        for (i, testcase) in config.testcases.iter().enumerate() {
+            let output = output_dir.join(format!("{:02}", i + 1));
+            results.push(judge(
+                cpd,
+                &solution.sourcefile,
+                testcase,
+                &output,
+                config,
+                &limits,
+            )?);
+        }
+
+        let verdicts: Vec<Verdict> = results.iter().map(|r| r.verdict).collect();
+        reports.push(SolutionReport {
+            name,
+            expected: solution.verdict.clone(),
+            scores: group_scores(config, &verdicts),
+            expected_score: solution.score,
+            results,
+        });
+    }
+
+    Ok(reports)
+}
+
+fn solution_name(solution: &Solution) -> String {
+    solution
+        .sourcefile
+        .source
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn judge(
+    cpd: &Path,
+    program: &SourceFile,
+    testcase: &Testcase,
+    output: &Path,
+    config: &ProblemConfig,
+    limits: &Limits,
+) -> io::Result<TestResult> {
+    let (report, interaction) = if config.interactive {
+        let interaction = interact(cpd, config, program, &testcase.input_path, output, output)?;
+        (interaction.solution, Some(interaction.result))
+    } else {
+        let options = RunOptions {
+            stdin: Some(testcase.input_path.clone()),
+            stdout: Some(output.to_path_buf()),
+            stderr: Some(output.with_extension("err")),
+            ..Default::default()
+        };
+        (program.run(cpd, limits, &options)?, None)
+    };
+
+    let mut result = TestResult {
+        verdict: Verdict::AC,
+        time: report.cpu_time,
+        crash: None,
+        comment: None,
+        points: None,
+    };
+
+    // A solution that is cut off by the interactor may crash on the closed pipe,
+    // so the verdict of the interactor comes before the crash of the solution.
+    if let Some(verdict) = report.limit_verdict(limits) {
+        result.verdict = verdict;
+    } else if let Some(interaction) = interaction.filter(|r| r.verdict != CheckerVerdict::Ok) {
+        result.set_checker_result(interaction);
+    } else if let Some(crash) = report.crash() {
+        result.verdict = Verdict::RE;
+        result.crash = Some(crash);
+    } else {
+        let checked = check(
+            cpd,
+            config,
+            &testcase.input_path,
+            output,
+            &testcase.output_path,
+        )?;
+        result.set_checker_result(checked);
+    }
+
+    Ok(result)
+}
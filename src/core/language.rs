@@ -0,0 +1,176 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// A programming language sources can be written in, along with the toolchain used for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    C,
+    Cpp11,
+    Cpp14,
+    Cpp17,
+    Cpp20,
+    Rust,
+    Python3,
+    Pypy3,
+    Java,
+    Kotlin,
+    Go,
+    Pascal,
+}
+
+/// How sources of a language are compiled and run.
+///
+/// The arguments may contain placeholders such as `%source%` and `%bin%`,
+/// which are expanded when the source is built and run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    /// The compiler, or `None` if the source is run as it is.
+    #[serde(default)]
+    pub compiler: Option<String>,
+    #[serde(default)]
+    pub compiler_args: Vec<String>,
+    /// The program running the source, or `None` if the compiled binary is run directly.
+    #[serde(default)]
+    pub interpreter: Option<String>,
+    #[serde(default)]
+    pub interpreter_args: Vec<String>,
+}
+
+impl Toolchain {
+    fn new(
+        compiler: Option<&str>,
+        compiler_args: &[&str],
+        interpreter: Option<&str>,
+        interpreter_args: &[&str],
+    ) -> Self {
+        let strings = |args: &[&str]| args.iter().map(|s| s.to_string()).collect();
+        Toolchain {
+            compiler: compiler.map(str::to_string),
+            compiler_args: strings(compiler_args),
+            interpreter: interpreter.map(str::to_string),
+            interpreter_args: strings(interpreter_args),
+        }
+    }
+}
+
+impl Language {
+    pub const ALL: [Language; 12] = [
+        Language::C,
+        Language::Cpp11,
+        Language::Cpp14,
+        Language::Cpp17,
+        Language::Cpp20,
+        Language::Rust,
+        Language::Python3,
+        Language::Pypy3,
+        Language::Java,
+        Language::Kotlin,
+        Language::Go,
+        Language::Pascal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::C => "c",
+            Language::Cpp11 => "cpp11",
+            Language::Cpp14 => "cpp14",
+            Language::Cpp17 => "cpp17",
+            Language::Cpp20 => "cpp20",
+            Language::Rust => "rust",
+            Language::Python3 => "python3",
+            Language::Pypy3 => "pypy3",
+            Language::Java => "java",
+            Language::Kotlin => "kotlin",
+            Language::Go => "go",
+            Language::Pascal => "pascal",
+        }
+    }
+
+    /// Guesses the language of a source from its extension.
+    /// C++ sources default to C++17 and Python sources to CPython.
+    pub fn from_path(path: &Path) -> Option<Language> {
+        match path.extension()?.to_str()? {
+            "c" => Some(Language::C),
+            "cpp" | "cc" | "cxx" => Some(Language::Cpp17),
+            "rs" => Some(Language::Rust),
+            "py" => Some(Language::Python3),
+            "java" => Some(Language::Java),
+            "kt" => Some(Language::Kotlin),
+            "go" => Some(Language::Go),
+            "pas" | "dpr" => Some(Language::Pascal),
+            _ => None,
+        }
+    }
+
+    /// Finds a language by its name, such as "cpp17".
+    pub fn from_name(name: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The built-in toolchain of the language.
+    pub fn toolchain(&self) -> Toolchain {
+        let cpp = |standard| {
+            Toolchain::new(
+                Some("g++"),
+                &[standard, "-O2", "%source%", "-o", "%bin%"],
+                None,
+                &[],
+            )
+        };
+
+        match self {
+            Language::C => Toolchain::new(
+                Some("gcc"),
+                &["-std=c11", "-O2", "%source%", "-o", "%bin%", "-lm"],
+                None,
+                &[],
+            ),
+            Language::Cpp11 => cpp("-std=c++11"),
+            Language::Cpp14 => cpp("-std=c++14"),
+            Language::Cpp17 => cpp("-std=c++17"),
+            Language::Cpp20 => cpp("-std=c++20"),
+            Language::Rust => Toolchain::new(
+                Some("rustc"),
+                &["--edition=2021", "-O", "%source%", "-o", "%bin%"],
+                None,
+                &[],
+            ),
+            Language::Python3 => Toolchain::new(None, &[], Some("python3"), &["%source%"]),
+            Language::Pypy3 => Toolchain::new(None, &[], Some("pypy3"), &["%source%"]),
+            // The class files of every Java source go to their own directory,
+            // so that classes with the same name in different sources do not clash.
+            Language::Java => Toolchain::new(
+                Some("javac"),
+                &["-d", "%dir%/%stem%.java.d", "%source%"],
+                Some("java"),
+                &["-cp", "%dir%/%stem%.java.d", "%stem%"],
+            ),
+            Language::Kotlin => Toolchain::new(
+                Some("kotlinc"),
+                &["%source%", "-include-runtime", "-d", "%dir%/%stem%.jar"],
+                Some("java"),
+                &["-jar", "%dir%/%stem%.jar"],
+            ),
+            Language::Go => {
+                Toolchain::new(Some("go"), &["build", "-o", "%bin%", "%source%"], None, &[])
+            }
+            Language::Pascal => Toolchain::new(
+                Some("fpc"),
+                &["-O2", "-FU%dir%", "-o%bin%", "%source%"],
+                None,
+                &[],
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
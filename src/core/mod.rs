@@ -1,12 +1,32 @@
+pub mod checker;
+pub mod config;
+pub mod generate;
+pub mod group;
+pub mod interactor;
+pub mod invoke;
+pub mod language;
+pub mod polygon;
+pub mod render;
 pub mod solution;
 pub mod source;
+pub mod statement;
+pub mod stress;
+pub mod testcase;
+pub mod tutorial;
+pub mod validate;
 
+use checker::Checker;
+use generate::GeneratorCall;
+use group::{check_dependencies, TestGroup};
 use serde::{Deserialize, Serialize};
 use solution::Solution;
-use source::SourceFile;
+use source::{Limits, SourceFile};
+use statement::Statement;
 use std::error::Error;
 use std::fs::{create_dir, File};
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::time::Duration;
+use testcase::Testcase;
 
 type GenericResult = Result<(), Box<dyn Error>>;
 
@@ -14,30 +34,67 @@ type GenericResult = Result<(), Box<dyn Error>>;
 pub struct ProblemConfig {
     pub title: String,
     pub time: f32,
+    /// The memory limit in megabytes
+    #[serde(default = "default_memory")]
+    pub memory: u32,
     pub tags: Vec<String>,
     pub testcases: Vec<Testcase>,
+    /// The scored groups of testcases
+    #[serde(default)]
+    pub groups: Vec<TestGroup>,
     pub sources: Vec<SourceFile>,
     pub solutions: Vec<Solution>,
     pub validator: Option<usize>,
-    pub checker: Option<usize>,
+    pub checker: Option<Checker>,
+    #[serde(default)]
+    pub main_solution: Option<usize>,
+    /// The generator invocations producing the generated testcases
+    #[serde(default)]
+    pub script: Vec<GeneratorCall>,
+    /// Whether solutions talk to the interactor instead of reading the input
+    #[serde(default)]
+    pub interactive: bool,
+    #[serde(default)]
+    pub interactor: Option<usize>,
+    /// The statements of the problem, one per language
+    #[serde(default)]
+    pub statements: Vec<Statement>,
 }
 
 impl ProblemConfig {
     pub fn from_file(file: File) -> serde_json::Result<Self> {
-        serde_json::from_reader(file)
+        let config: Self = serde_json::from_reader(file)?;
+        check_dependencies(&config.groups).map_err(serde::de::Error::custom)?;
+        Ok(config)
     }
 
     pub fn save_to_file(&self, file: File) -> serde_json::Result<()> {
         serde_json::to_writer_pretty(file, self)
     }
+
+    /// The limits applied to solutions of this problem.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            time: Some(Duration::from_secs_f32(self.time)),
+            memory: Some(self.memory),
+        }
+    }
+
+    /// The limits applied to the generators, validator and checker of this problem.
+    /// They are only limited in time, so that a hanging one fails instead of running forever.
+    pub fn helper_limits(&self) -> Limits {
+        Limits {
+            time: Some(Duration::from_secs_f32(self.time) * HELPER_TIME_FACTOR),
+            memory: None,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Testcase {
-    pub input_path: PathBuf,
-    pub output_path: PathBuf,
-    pub generate: bool,
-    pub sample: bool,
+/// The multiple of the time limit the generators, validator and checker may run for.
+const HELPER_TIME_FACTOR: u32 = 10;
+
+fn default_memory() -> u32 {
+    256
 }
 
 pub fn is_valid_problem_name(name: &str) -> bool {
@@ -47,7 +104,7 @@ pub fn is_valid_problem_name(name: &str) -> bool {
 
 pub fn reformat_valid_name(name: &str) -> String {
     assert!(is_valid_problem_name(name));
-    name.split(|c| c == '-')
+    name.split('-')
         .map(|s| {
             s.chars()
                 .take(1)
@@ -62,6 +119,7 @@ pub fn reformat_valid_name(name: &str) -> String {
 /// The Directory of the problem has this structure
 /// problem/ # the problem directory
 /// -- problem_config.json
+/// -- rustygon_config.json # optional, overrides the user config for this problem
 /// -- src/  # the sources directory
 /// -- -- sources/ # contains source files for generator and validator and checker
 /// -- -- solutions/ # contains the sources of the solutions
@@ -69,6 +127,9 @@ pub fn reformat_valid_name(name: &str) -> String {
 /// -- -- input/ # the input of the testcases
 /// -- -- output/ # the output of the testcases
 /// -- text/ # contains all text files such as statement, tutorials, and testcases discriptions
+/// -- -- statement/ # the statements, one directory per language such as en/
+/// -- -- -- en/ # the sections of a statement: legend.md, input.md, output.md, scoring.md, notes.md
+/// -- -- tutorial/ # the tutorials, one file per language such as en.md
 /// -- bin/ # contains all binary compiled from the source files
 pub fn create_problem_dir(path: &Path, config: &ProblemConfig) -> GenericResult {
     create_dir(path)?;
@@ -98,3 +159,64 @@ fn modify_config(
     config.save_to_file(config_file)?;
     Ok(())
 }
+
+pub fn set_memory(cpd: &Path, memory: u32) -> GenericResult {
+    modify_config(cpd, |config| {
+        config.memory = memory;
+        Ok(())
+    })
+}
+
+/// Helpers shared by the tests of the modules.
+#[cfg(test)]
+pub mod testing {
+    use super::{create_problem_dir, ProblemConfig};
+    use serde_json::json;
+    use std::fs::remove_dir_all;
+    use std::path::{Path, PathBuf};
+
+    /// A directory removed when dropped, so that it is cleaned up even if the test fails.
+    pub struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// A directory named after `name` in the temporary directory of the system.
+        /// It is not created.
+        pub fn new(name: &str) -> Self {
+            let name = format!("rustygon-{name}-{}", std::process::id());
+            TempDir::at(std::env::temp_dir().join(name))
+        }
+
+        /// The directory at `path`, which is removed first if it exists.
+        pub fn at(path: PathBuf) -> Self {
+            let _ = remove_dir_all(&path);
+            TempDir(path)
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = remove_dir_all(&self.0);
+        }
+    }
+
+    /// Creates a problem without any component in a temporary directory.
+    pub fn temp_problem(name: &str, time: f32, memory: u32) -> TempDir {
+        let dir = TempDir::new(name);
+        let config: ProblemConfig = serde_json::from_value(json!({
+            "title": "",
+            "time": time,
+            "memory": memory,
+            "tags": [],
+            "testcases": [],
+            "sources": [],
+            "solutions": [],
+        }))
+        .unwrap();
+        create_problem_dir(dir.path(), &config).unwrap();
+        dir
+    }
+}
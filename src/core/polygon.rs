@@ -0,0 +1,1165 @@
+use super::checker::standard::StandardChecker;
+use super::checker::Checker;
+use super::config::Config;
+use super::group::{check_dependencies, TestGroup};
+use super::language::Language;
+use super::render::{
+    escape_html, escape_latex, example_name, markdown_to_latex, read_samples, render_html,
+    render_latex,
+};
+use super::solution::{ExpectedVerdict, Preset, Solution, Verdict};
+use super::source::SourceFile;
+use super::statement::{is_valid_language, read_sections, statement_dir, Section};
+use super::testcase::{testcase_paths, Testcase};
+use super::tutorial::{read_tutorial, tutorial_path};
+use super::validate::ensure_valid;
+use super::{create_problem_dir, GenericResult, ProblemConfig};
+use roxmltree::{Document, Node};
+use std::collections::BTreeMap;
+use std::env::current_dir;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, read, read_dir, write, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// The Polygon tag of a solution expected to get `verdict`,
+/// or `None` if Polygon has no tag for it.
+pub fn polygon_tag(verdict: &ExpectedVerdict) -> Option<&'static str> {
+    use Verdict::*;
+    match verdict {
+        ExpectedVerdict::Preset(preset) => Some(match preset {
+            Preset::Main => "main",
+            Preset::Correct => "accepted",
+            Preset::Rejected => "rejected",
+            Preset::Failed => "failed",
+            Preset::TimeLimitOrAccepted => "time-limit-exceeded-or-accepted",
+            Preset::TimeLimitOrMemoryLimit => "time-limit-exceeded-or-memory-limit-exceeded",
+        }),
+        ExpectedVerdict::Verdict(verdict) => match verdict {
+            AC => Some("accepted"),
+            WA => Some("wrong-answer"),
+            TLE => Some("time-limit-exceeded"),
+            MLE => Some("memory-limit-exceeded"),
+            RE => Some("failed"),
+            PE => Some("presentation-error"),
+            FAIL => None,
+        },
+        ExpectedVerdict::AnyOf(verdicts) => {
+            let mut verdicts = verdicts.clone();
+            verdicts.sort_by_key(|verdict| *verdict as u8);
+            verdicts.dedup();
+            match verdicts[..] {
+                [verdict] => polygon_tag(&ExpectedVerdict::Verdict(verdict)),
+                [AC, TLE] => Some("time-limit-exceeded-or-accepted"),
+                [TLE, MLE] => Some("time-limit-exceeded-or-memory-limit-exceeded"),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The Polygon type of a source, such as "cpp.g++17", or `None` if it is not known.
+pub fn polygon_source_type(source: &SourceFile) -> Option<&'static str> {
+    let has_arg = |arg: &str| source.compiler_args.iter().any(|a| a == arg);
+    let language = Language::from_path(&source.source)?;
+    Some(match language {
+        Language::C => "c.gcc",
+        Language::Cpp11 | Language::Cpp14 | Language::Cpp17 | Language::Cpp20 => {
+            if has_arg("-std=c++20") {
+                "cpp.gcc13-64-winlibs-g++20"
+            } else if has_arg("-std=c++14") {
+                "cpp.g++14"
+            } else if has_arg("-std=c++11") {
+                "cpp.g++11"
+            } else {
+                "cpp.g++17"
+            }
+        }
+        Language::Rust => "rust",
+        Language::Python3 | Language::Pypy3 => {
+            if source.bin.as_os_str() == "pypy3" {
+                "python.pypy3"
+            } else {
+                "python.3"
+            }
+        }
+        Language::Java => "java21",
+        Language::Kotlin => "kotlin",
+        Language::Go => "go",
+        Language::Pascal => "pas.fpc",
+    })
+}
+
+/// The name Polygon gives to a language code, such as "english" for "en".
+pub fn polygon_language(code: &str) -> &str {
+    POLYGON_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(code, |(_, name)| name)
+}
+
+pub const POLYGON_LANGUAGES: [(&str, &str); 12] = [
+    ("en", "english"),
+    ("ru", "russian"),
+    ("ar", "arabic"),
+    ("uk", "ukrainian"),
+    ("fr", "french"),
+    ("de", "german"),
+    ("es", "spanish"),
+    ("pt", "portuguese"),
+    ("it", "italian"),
+    ("zh", "chinese"),
+    ("ja", "japanese"),
+    ("ko", "korean"),
+];
+
+/// Writes the problem at `cpd` as a Polygon package zipped into `output`.
+///
+/// The package holds "problem.xml" describing the problem, the sources in "files",
+/// the solutions in "solutions", the testcases in "tests" and the statements and
+/// tutorials in "statements". Every testcase must be valid and have its output.
+pub fn export_polygon(cpd: &Path, config: &ProblemConfig, output: &Path) -> GenericResult {
+    ensure_valid(cpd, config)?;
+    if let Some(n) = config
+        .testcases
+        .iter()
+        .position(|testcase| !testcase.output_path.exists())
+    {
+        return Err(format!("Test {} has no output. Generate the outputs first", n + 1).into());
+    }
+
+    let mut tags = Vec::with_capacity(config.solutions.len());
+    for solution in &config.solutions {
+        let name = file_name(&solution.sourcefile);
+        let tag = polygon_tag(&solution.verdict).ok_or(format!(
+            "Polygon has no tag for the expected verdict of {name}: {:?}",
+            solution.verdict
+        ))?;
+        tags.push(tag);
+    }
+
+    let mut zip = ZipWriter::new(File::create(output)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: &str, content: &[u8]| -> GenericResult {
+        zip.start_file(name, options)?;
+        zip.write_all(content)?;
+        Ok(())
+    };
+
+    for source in &config.sources {
+        add(
+            &format!("files/{}", file_name(source)),
+            &read(&source.source)?,
+        )?;
+    }
+    let resources = resources(cpd, config)?;
+    for resource in &resources {
+        add(
+            &format!("files/{resource}"),
+            &read(cpd.join("src").join("sources").join(resource))?,
+        )?;
+    }
+    for solution in &config.solutions {
+        let source = &solution.sourcefile;
+        add(
+            &format!("solutions/{}", file_name(source)),
+            &read(&source.source)?,
+        )?;
+    }
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        add(&format!("tests/{:02}", i + 1), &read(&testcase.input_path)?)?;
+        add(
+            &format!("tests/{:02}.a", i + 1),
+            &read(&testcase.output_path)?,
+        )?;
+    }
+
+    let samples = read_samples(config)?;
+    for statement in &config.statements {
+        let dir = format!("statements/{}", polygon_language(&statement.language));
+        add(
+            &format!("{dir}/name.tex"),
+            escape_latex(&config.title).as_bytes(),
+        )?;
+        for (section, text) in read_sections(cpd, &statement.language)? {
+            let latex = markdown_to_latex(&text);
+            add(&format!("{dir}/{}.tex", section.name()), latex.as_bytes())?;
+        }
+        for (i, sample) in samples.iter().enumerate() {
+            let example = example_name(i + 1);
+            add(&format!("{dir}/{example}"), sample.input.as_bytes())?;
+            add(&format!("{dir}/{example}.a"), sample.output.as_bytes())?;
+        }
+        if tutorial_path(cpd, &statement.language).exists() {
+            let tutorial = read_tutorial(cpd, config, &statement.language)?;
+            add(
+                &format!("{dir}/tutorial.tex"),
+                markdown_to_latex(&tutorial).as_bytes(),
+            )?;
+        }
+
+        let latex = render_latex(cpd, config, statement)?;
+        add(&format!("{dir}/problem.tex"), latex.as_bytes())?;
+        let html = render_html(cpd, config, statement)?;
+        let language = polygon_language(&statement.language);
+        add(
+            &format!("statements/.html/{language}/problem.html"),
+            html.as_bytes(),
+        )?;
+    }
+
+    add(
+        "problem.xml",
+        problem_xml(cpd, config, &tags, &resources)?.as_bytes(),
+    )?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// The files in "{cpd}/src/sources" that are not sources of the problem, such as testlib.h.
+fn resources(cpd: &Path, config: &ProblemConfig) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut resources = Vec::new();
+    for entry in read_dir(cpd.join("src").join("sources"))? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name();
+        if config
+            .sources
+            .iter()
+            .all(|source| source.source.file_name() != Some(&name))
+        {
+            resources.push(name.to_string_lossy().into_owned());
+        }
+    }
+    resources.sort();
+    Ok(resources)
+}
+
+fn problem_xml(
+    cpd: &Path,
+    config: &ProblemConfig,
+    tags: &[&str],
+    resources: &[String],
+) -> Result<String, Box<dyn Error>> {
+    let short_name = cpd.file_name().unwrap().to_string_lossy();
+    let commands = generator_commands(config);
+    let checker = match config.checker {
+        Some(Checker::Source(i)) => Some(i),
+        _ => None,
+    };
+    let roles = [config.validator, checker, config.interactor];
+
+    let mut xml = String::new();
+    writeln!(
+        xml,
+        "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"no\"?>"
+    )?;
+    writeln!(
+        xml,
+        "<problem revision=\"1\" short-name=\"{}\">",
+        escape_html(&short_name)
+    )?;
+
+    writeln!(xml, "    <names>")?;
+    for statement in &config.statements {
+        writeln!(
+            xml,
+            "        <name language=\"{}\" value=\"{}\"/>",
+            escape_html(polygon_language(&statement.language)),
+            escape_html(&config.title)
+        )?;
+    }
+    writeln!(xml, "    </names>")?;
+
+    writeln!(xml, "    <statements>")?;
+    for statement in &config.statements {
+        let language = escape_html(polygon_language(&statement.language));
+        writeln!(xml, "        <statement charset=\"UTF-8\" language=\"{language}\" mathjax=\"true\" path=\"statements/{language}/problem.tex\" type=\"application/x-tex\"/>")?;
+        writeln!(xml, "        <statement charset=\"UTF-8\" language=\"{language}\" mathjax=\"true\" path=\"statements/.html/{language}/problem.html\" type=\"text/html\"/>")?;
+    }
+    writeln!(xml, "    </statements>")?;
+
+    writeln!(xml, "    <tutorials>")?;
+    for statement in &config.statements {
+        if tutorial_path(cpd, &statement.language).exists() {
+            let language = escape_html(polygon_language(&statement.language));
+            writeln!(xml, "        <tutorial charset=\"UTF-8\" language=\"{language}\" mathjax=\"true\" path=\"statements/{language}/tutorial.tex\" type=\"application/x-tex\"/>")?;
+        }
+    }
+    writeln!(xml, "    </tutorials>")?;
+
+    writeln!(
+        xml,
+        "    <judging input-file=\"\" output-file=\"\" run-count=\"1\">"
+    )?;
+    writeln!(xml, "        <testset name=\"tests\">")?;
+    writeln!(
+        xml,
+        "            <time-limit>{}</time-limit>",
+        (config.time * 1000.0).round() as u64
+    )?;
+    writeln!(
+        xml,
+        "            <memory-limit>{}</memory-limit>",
+        config.memory as u64 * 1024 * 1024
+    )?;
+    writeln!(
+        xml,
+        "            <test-count>{}</test-count>",
+        config.testcases.len()
+    )?;
+    writeln!(
+        xml,
+        "            <input-path-pattern>tests/%02d</input-path-pattern>"
+    )?;
+    writeln!(
+        xml,
+        "            <answer-path-pattern>tests/%02d.a</answer-path-pattern>"
+    )?;
+    writeln!(xml, "            <tests>")?;
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        let mut attributes = String::new();
+        match commands.get(&(i + 1)) {
+            Some(command) => write!(
+                attributes,
+                " cmd=\"{}\" method=\"generated\"",
+                escape_html(command)
+            )?,
+            None => write!(attributes, " method=\"manual\"")?,
+        }
+        if testcase.sample {
+            write!(attributes, " sample=\"true\"")?;
+        }
+        if let Some(group) = &testcase.group {
+            write!(attributes, " group=\"{}\"", escape_html(group))?;
+        }
+        writeln!(xml, "                <test{attributes}/>")?;
+    }
+    writeln!(xml, "            </tests>")?;
+    if !config.groups.is_empty() {
+        writeln!(xml, "            <groups>")?;
+        for group in &config.groups {
+            let head = format!(
+                "<group feedback-policy=\"complete\" name=\"{}\" points=\"{}\" points-policy=\"complete-group\"",
+                escape_html(&group.name),
+                group.points
+            );
+            if group.dependencies.is_empty() {
+                writeln!(xml, "                {head}/>")?;
+                continue;
+            }
+            writeln!(xml, "                {head}>")?;
+            writeln!(xml, "                    <dependencies>")?;
+            for dependency in &group.dependencies {
+                writeln!(
+                    xml,
+                    "                        <dependency group=\"{}\"/>",
+                    escape_html(dependency)
+                )?;
+            }
+            writeln!(xml, "                    </dependencies>")?;
+            writeln!(xml, "                </group>")?;
+        }
+        writeln!(xml, "            </groups>")?;
+    }
+    writeln!(xml, "        </testset>")?;
+    writeln!(xml, "    </judging>")?;
+
+    writeln!(xml, "    <files>")?;
+    writeln!(xml, "        <resources>")?;
+    for resource in resources {
+        writeln!(
+            xml,
+            "            <file path=\"files/{}\"/>",
+            escape_html(resource)
+        )?;
+    }
+    writeln!(xml, "        </resources>")?;
+    writeln!(xml, "        <executables>")?;
+    for (i, source) in config.sources.iter().enumerate() {
+        if roles.contains(&Some(i)) {
+            continue;
+        }
+        writeln!(xml, "            <executable>")?;
+        writeln!(xml, "                {}", source_xml("files", source))?;
+        writeln!(xml, "            </executable>")?;
+    }
+    writeln!(xml, "        </executables>")?;
+    writeln!(xml, "    </files>")?;
+
+    writeln!(xml, "    <assets>")?;
+    match &config.checker {
+        Some(Checker::Source(i)) => {
+            writeln!(xml, "        <checker type=\"testlib\">")?;
+            writeln!(
+                xml,
+                "            {}",
+                source_xml("files", &config.sources[*i])
+            )?;
+            writeln!(xml, "        </checker>")?;
+        }
+        Some(Checker::Standard(checker)) => {
+            writeln!(
+                xml,
+                "        <checker name=\"std::{}.cpp\" type=\"testlib\"/>",
+                checker.name()
+            )?;
+        }
+        None => {
+            writeln!(
+                xml,
+                "        <checker name=\"std::wcmp.cpp\" type=\"testlib\"/>"
+            )?;
+        }
+    }
+    if let Some(i) = config.interactor {
+        writeln!(xml, "        <interactor>")?;
+        writeln!(
+            xml,
+            "            {}",
+            source_xml("files", &config.sources[i])
+        )?;
+        writeln!(xml, "        </interactor>")?;
+    }
+    if let Some(i) = config.validator {
+        writeln!(xml, "        <validators>")?;
+        writeln!(xml, "            <validator>")?;
+        writeln!(
+            xml,
+            "                {}",
+            source_xml("files", &config.sources[i])
+        )?;
+        writeln!(xml, "            </validator>")?;
+        writeln!(xml, "        </validators>")?;
+    }
+    writeln!(xml, "        <solutions>")?;
+    for (solution, tag) in config.solutions.iter().zip(tags) {
+        writeln!(xml, "            <solution tag=\"{tag}\">")?;
+        writeln!(
+            xml,
+            "                {}",
+            source_xml("solutions", &solution.sourcefile)
+        )?;
+        writeln!(xml, "            </solution>")?;
+    }
+    writeln!(xml, "        </solutions>")?;
+    writeln!(xml, "    </assets>")?;
+
+    writeln!(xml, "    <tags>")?;
+    for tag in &config.tags {
+        writeln!(xml, "        <tag value=\"{}\"/>", escape_html(tag))?;
+    }
+    writeln!(xml, "    </tags>")?;
+    writeln!(xml, "</problem>")?;
+    Ok(xml)
+}
+
+/// The `source` element of a source inside the directory `dir` of the package.
+fn source_xml(dir: &str, source: &SourceFile) -> String {
+    let path = escape_html(&format!("{dir}/{}", file_name(source)));
+    match polygon_source_type(source) {
+        Some(kind) => format!("<source path=\"{path}\" type=\"{kind}\"/>"),
+        None => format!("<source path=\"{path}\"/>"),
+    }
+}
+
+/// The command generating every generated testcase, by testcase number.
+///
+/// Script lines with a target generate that testcase, and the other lines generate
+/// the remaining generated testcases in order, as in `generate_tests`.
+fn generator_commands(config: &ProblemConfig) -> BTreeMap<usize, String> {
+    let command = |i: usize| {
+        let call = &config.script[i];
+        let generator = Path::new(&call.generator)
+            .file_stem()
+            .map_or(call.generator.clone(), |s| s.to_string_lossy().into_owned());
+        std::iter::once(generator)
+            .chain(call.args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut commands: BTreeMap<usize, String> = config
+        .script
+        .iter()
+        .enumerate()
+        .filter_map(|(i, call)| Some((call.test?, command(i))))
+        .collect();
+
+    let mut free = config
+        .script
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| call.test.is_none())
+        .map(|(i, _)| command(i));
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        if testcase.generate && !commands.contains_key(&(i + 1)) {
+            if let Some(command) = free.next() {
+                commands.insert(i + 1, command);
+            }
+        }
+    }
+    commands
+}
+
+fn file_name(source: &SourceFile) -> String {
+    source
+        .source
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Guesses the language of a source from its Polygon type, such as "cpp.g++17".
+pub fn language_from_polygon_type(kind: &str) -> Option<Language> {
+    let (family, compiler) = kind.split_once('.').unwrap_or((kind, ""));
+    match family {
+        "c" => Some(Language::C),
+        "cpp" if compiler.ends_with("++20") => Some(Language::Cpp20),
+        "cpp" if compiler.ends_with("++14") => Some(Language::Cpp14),
+        "cpp" if compiler.ends_with("++11") => Some(Language::Cpp11),
+        "cpp" => Some(Language::Cpp17),
+        "python" if compiler.starts_with("pypy3") => Some(Language::Pypy3),
+        "python" if compiler == "3" => Some(Language::Python3),
+        "pas" | "delphi" => Some(Language::Pascal),
+        family if family.starts_with("java") => Some(Language::Java),
+        family if family.starts_with("kotlin") => Some(Language::Kotlin),
+        family if family.starts_with("rust") => Some(Language::Rust),
+        family if family.starts_with("go") => Some(Language::Go),
+        _ => None,
+    }
+}
+
+/// The expected verdict of a solution with a Polygon tag, or `None` for an unknown tag.
+pub fn verdict_from_polygon_tag(tag: &str) -> Option<ExpectedVerdict> {
+    use Verdict::*;
+    Some(match tag {
+        "main" => ExpectedVerdict::Preset(Preset::Main),
+        "accepted" => ExpectedVerdict::Verdict(AC),
+        "rejected" => ExpectedVerdict::Preset(Preset::Rejected),
+        "wrong-answer" => ExpectedVerdict::Verdict(WA),
+        "time-limit-exceeded" => ExpectedVerdict::Verdict(TLE),
+        "memory-limit-exceeded" => ExpectedVerdict::Verdict(MLE),
+        "failed" => ExpectedVerdict::Verdict(RE),
+        "presentation-error" => ExpectedVerdict::Verdict(PE),
+        "time-limit-exceeded-or-accepted" => ExpectedVerdict::Preset(Preset::TimeLimitOrAccepted),
+        "time-limit-exceeded-or-memory-limit-exceeded" => {
+            ExpectedVerdict::Preset(Preset::TimeLimitOrMemoryLimit)
+        }
+        _ => return None,
+    })
+}
+
+/// A Polygon package, either zipped or extracted to a directory.
+enum Package {
+    Dir(PathBuf),
+    Zip(ZipArchive<File>),
+}
+
+impl Package {
+    fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if path.is_dir() {
+            Ok(Package::Dir(path.to_path_buf()))
+        } else {
+            Ok(Package::Zip(ZipArchive::new(File::open(path)?)?))
+        }
+    }
+
+    /// Reads a file of the package, or returns `None` if it does not exist.
+    fn read(&mut self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        match self {
+            Package::Dir(dir) => {
+                let path = dir.join(name);
+                Ok(path.is_file().then(|| read(path)).transpose()?)
+            }
+            Package::Zip(zip) => match zip.by_name(name) {
+                Ok(mut file) => {
+                    let mut content = Vec::new();
+                    file.read_to_end(&mut content)?;
+                    Ok(Some(content))
+                }
+                Err(ZipError::FileNotFound) => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+}
+
+/// A problem read from a Polygon package, along with the files to write in its directory.
+struct Import<'a> {
+    cpd: &'a Path,
+    settings: &'a Config,
+    package: Package,
+    config: ProblemConfig,
+    files: Vec<(PathBuf, Vec<u8>)>,
+    warnings: Vec<String>,
+}
+
+/// Creates a problem directory from the Polygon package at `package`, which is
+/// a zip archive or a directory holding "problem.xml".
+///
+/// The problem is created at `directory`, or in the current directory under the short
+/// name of the package if `None`. Sources and solutions get the toolchains of `settings`,
+/// solutions get the verdicts of their tags, the testcases and groups are copied, and the
+/// statements and tutorials are kept as LaTeX. Returns the problem directory and a warning
+/// for everything in the package that could not be imported.
+pub fn import_polygon(
+    package: &Path,
+    directory: Option<&Path>,
+    settings: &Config,
+) -> Result<(PathBuf, Vec<String>), Box<dyn Error>> {
+    let mut package = Package::open(package)?;
+    let xml = package
+        .read("problem.xml")?
+        .ok_or("The package has no problem.xml")?;
+    let xml = String::from_utf8(xml)?;
+    let document = Document::parse(&xml)?;
+    let problem = document.root_element();
+
+    let short_name = problem.attribute("short-name").unwrap_or_default();
+    let title = problem
+        .descendants()
+        .filter(|node| node.has_tag_name("name"))
+        .max_by_key(|node| node.attribute("language") == Some("english"))
+        .and_then(|node| node.attribute("value"))
+        .unwrap_or(short_name);
+    let cpd = match directory {
+        Some(directory) => current_dir()?.join(directory),
+        None if !short_name.is_empty() => current_dir()?.join(short_name),
+        None => return Err("The package has no short name, give the directory".into()),
+    };
+
+    let mut import = Import {
+        cpd: &cpd,
+        settings,
+        package,
+        config: ProblemConfig {
+            title: title.to_string(),
+            time: 1.0,
+            memory: 256,
+            tags: Vec::new(),
+            testcases: Vec::new(),
+            groups: Vec::new(),
+            sources: Vec::new(),
+            solutions: Vec::new(),
+            validator: None,
+            checker: None,
+            main_solution: None,
+            script: Vec::new(),
+            interactive: false,
+            interactor: None,
+            statements: Vec::new(),
+        },
+        files: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    if let Some(judging) = child(problem, "judging") {
+        import.judging(judging)?;
+    }
+    if let Some(files) = child(problem, "files") {
+        import.files(files)?;
+    }
+    if let Some(assets) = child(problem, "assets") {
+        import.assets(assets)?;
+    }
+    import.statements(problem)?;
+    for tag in children(problem, "tags").flat_map(|tags| children(tags, "tag")) {
+        if let Some(value) = tag.attribute("value") {
+            import.config.tags.push(value.to_string());
+        }
+    }
+    for node in problem.children().filter(Node::is_element) {
+        let name = node.tag_name().name();
+        if !KNOWN_ELEMENTS.contains(&name) {
+            import.warn(format!("<{name}> was ignored"));
+        }
+    }
+
+    create_problem_dir(&cpd, &import.config)?;
+    for (path, content) in &import.files {
+        create_dir_all(path.parent().unwrap())?;
+        write(path, content)?;
+    }
+    let warnings = import.warnings;
+    Ok((cpd, warnings))
+}
+
+/// The elements of problem.xml that are imported, or have nothing to import.
+const KNOWN_ELEMENTS: [&str; 9] = [
+    "names",
+    "statements",
+    "tutorials",
+    "judging",
+    "files",
+    "assets",
+    "tags",
+    "properties",
+    "documents",
+];
+
+impl Import<'_> {
+    fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    fn judging(&mut self, judging: Node) -> GenericResult {
+        for attribute in ["input-file", "output-file"] {
+            if judging
+                .attribute(attribute)
+                .is_some_and(|file| !file.is_empty())
+            {
+                self.warn(format!(
+                    "{attribute} was ignored, solutions use the standard streams"
+                ));
+            }
+        }
+
+        for testset in children(judging, "testset") {
+            let name = testset.attribute("name").unwrap_or_default();
+            if name == "tests" {
+                self.testset(testset)?;
+            } else {
+                self.warn(format!("The testset {name} was ignored"));
+            }
+        }
+        Ok(())
+    }
+
+    fn testset(&mut self, testset: Node) -> GenericResult {
+        if let Some(time) = child_text(testset, "time-limit").and_then(|t| t.parse::<f32>().ok()) {
+            self.config.time = time / 1000.0;
+        }
+        if let Some(memory) =
+            child_text(testset, "memory-limit").and_then(|m| m.parse::<u64>().ok())
+        {
+            self.config.memory = (memory / (1024 * 1024)) as u32;
+        }
+        let input_pattern = child_text(testset, "input-path-pattern").unwrap_or("tests/%02d");
+        let answer_pattern = child_text(testset, "answer-path-pattern").unwrap_or("tests/%02d.a");
+
+        let mut missing = Vec::new();
+        for (i, test) in children(testset, "tests")
+            .flat_map(|tests| children(tests, "test"))
+            .enumerate()
+        {
+            let n = i + 1;
+            let (input_path, output_path) = testcase_paths(self.cpd, n);
+            let generate = test.attribute("method") == Some("generated");
+
+            match self.package.read(&test_path(input_pattern, n))? {
+                Some(input) => self.files.push((input_path.clone(), input)),
+                None if generate => missing.push(n.to_string()),
+                None => return Err(format!("The input of test {n} is missing").into()),
+            }
+            if let Some(answer) = self.package.read(&test_path(answer_pattern, n))? {
+                self.files.push((output_path.clone(), answer));
+            }
+
+            if generate {
+                let cmd = test.attribute("cmd").unwrap_or_default();
+                match format!("{cmd} > {n}").parse() {
+                    Ok(call) => self.config.script.push(call),
+                    Err(e) => self.warn(format!("The command of test {n} was ignored: {e}")),
+                }
+            }
+            if test.attribute("points").is_some() {
+                self.warn(format!("The points of test {n} were ignored"));
+            }
+
+            self.config.testcases.push(Testcase {
+                input_path,
+                output_path,
+                generate,
+                sample: test.attribute("sample") == Some("true"),
+                group: test.attribute("group").map(str::to_string),
+            });
+        }
+        if !missing.is_empty() {
+            self.warn(format!(
+                "Generated tests {} are missing, generate them with `rustygon gen tests`",
+                missing.join(", ")
+            ));
+        }
+
+        for group in children(testset, "groups").flat_map(|groups| children(groups, "group")) {
+            let name = group.attribute("name").unwrap_or_default().to_string();
+            let policy = group.attribute("points-policy").unwrap_or("complete-group");
+            if policy != "complete-group" {
+                self.warn(format!(
+                    "Group {name} scores {policy}, it is imported as complete-group"
+                ));
+            }
+            let dependencies = children(group, "dependencies")
+                .flat_map(|dependencies| children(dependencies, "dependency"))
+                .filter_map(|dependency| dependency.attribute("group"))
+                .map(str::to_string)
+                .collect();
+            self.config.groups.push(TestGroup {
+                name,
+                points: group
+                    .attribute("points")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0.0),
+                dependencies,
+            });
+        }
+        check_dependencies(&self.config.groups)?;
+        Ok(())
+    }
+
+    fn files(&mut self, files: Node) -> GenericResult {
+        for file in children(files, "resources").flat_map(|resources| children(resources, "file")) {
+            let path = file.attribute("path").unwrap_or_default();
+            let Some(name) = package_file_name(path) else {
+                self.warn(format!("The resource {path:?} was ignored"));
+                continue;
+            };
+            let Some(content) = self.package.read(path)? else {
+                self.warn(format!("The resource {path} is missing"));
+                continue;
+            };
+            let destination = self.cpd.join("src").join("sources").join(name);
+            self.files.push((destination, content));
+        }
+
+        for executable in children(files, "executables")
+            .flat_map(|executables| children(executables, "executable"))
+        {
+            if let Some(source) = child(executable, "source") {
+                self.source(source)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn assets(&mut self, assets: Node) -> GenericResult {
+        if let Some(checker) = child(assets, "checker") {
+            if checker
+                .attribute("type")
+                .is_some_and(|kind| kind != "testlib")
+            {
+                self.warn("The checker is not a testlib checker".to_string());
+            }
+            let standard = checker
+                .attribute("name")
+                .and_then(|name| name.parse::<StandardChecker>().ok());
+            self.config.checker = match (standard, child(checker, "source")) {
+                (Some(standard), _) => Some(Checker::Standard(standard)),
+                (None, Some(source)) => self.source(source)?.map(Checker::Source),
+                (None, None) => {
+                    self.warn("The checker has no source".to_string());
+                    None
+                }
+            };
+        }
+
+        if let Some(interactor) = child(assets, "interactor") {
+            self.config.interactive = true;
+            if let Some(source) = child(interactor, "source") {
+                self.config.interactor = self.source(source)?;
+            }
+        }
+
+        let validators: Vec<Node> = children(assets, "validators")
+            .flat_map(|validators| children(validators, "validator"))
+            .collect();
+        for (i, validator) in validators.into_iter().enumerate() {
+            let Some(source) = child(validator, "source") else {
+                continue;
+            };
+            let index = self.source(source)?;
+            if i == 0 {
+                self.config.validator = index;
+            } else {
+                self.warn(format!(
+                    "{} is added as a source, only one validator is used",
+                    source.attribute("path").unwrap_or_default()
+                ));
+            }
+        }
+
+        for solution in
+            children(assets, "solutions").flat_map(|solutions| children(solutions, "solution"))
+        {
+            if let Some(source) = child(solution, "source") {
+                let tag = solution.attribute("tag").unwrap_or_default();
+                self.solution(source, tag)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Imports the statements and tutorials written in LaTeX, one per language.
+    /// The LaTeX is kept next to where the Markdown of the statement and tutorial goes,
+    /// such as "text/statement/en/legend.tex", for the sections to be rewritten from.
+    /// No statement is added, since the renderers only read Markdown.
+    fn statements(&mut self, problem: Node) -> GenericResult {
+        let mut imported = Vec::new();
+        for statement in children(problem, "statements").flat_map(|s| children(s, "statement")) {
+            let (Some(language), Some(path)) =
+                (statement.attribute("language"), statement.attribute("path"))
+            else {
+                continue;
+            };
+            if statement.attribute("type") != Some("application/x-tex")
+                || imported.contains(&language)
+            {
+                continue;
+            }
+            imported.push(language);
+
+            let Some(code) = language_code(language) else {
+                self.warn(format!("The statement in {language} was ignored"));
+                continue;
+            };
+            let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+            for section in Section::ALL {
+                let file = format!("{}.tex", section.name());
+                if let Some(content) = self.package.read(&dir.join(&file).to_string_lossy())? {
+                    self.files
+                        .push((statement_dir(self.cpd, &code).join(file), content));
+                }
+            }
+            if self
+                .package
+                .read(&dir.join("interaction.tex").to_string_lossy())?
+                .is_some()
+            {
+                self.warn(format!("The interaction section in {language} was ignored"));
+            }
+            if let Some(content) = self
+                .package
+                .read(&dir.join("tutorial.tex").to_string_lossy())?
+            {
+                let path = tutorial_path(self.cpd, &code).with_extension("tex");
+                self.files.push((path, content));
+            }
+
+            self.warn(format!(
+                "The statement in {language} is kept as LaTeX in text/statement/{code}, \
+                 add it with `rustygon add statement --lang {code}` and write its sections"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Imports a source from its `source` element. Returns its index in the sources,
+    /// or `None` if its language is unknown.
+    fn source(&mut self, source: Node) -> Result<Option<usize>, Box<dyn Error>> {
+        let path = source.attribute("path").unwrap_or_default();
+        let Some(name) = package_file_name(path) else {
+            self.warn(format!("The source {path:?} was ignored"));
+            return Ok(None);
+        };
+        if let Some(i) = self
+            .config
+            .sources
+            .iter()
+            .position(|s| s.source.file_name().unwrap().eq(name))
+        {
+            return Ok(Some(i));
+        }
+
+        let destination = self.cpd.join("src").join("sources").join(name);
+        let Some(sourcefile) = self.sourcefile(&destination, source)? else {
+            return Ok(None);
+        };
+        self.config.sources.push(sourcefile);
+        Ok(Some(self.config.sources.len() - 1))
+    }
+
+    fn solution(&mut self, source: Node, tag: &str) -> GenericResult {
+        let path = source.attribute("path").unwrap_or_default();
+        let Some(name) = package_file_name(path) else {
+            self.warn(format!("The solution {path:?} was ignored"));
+            return Ok(());
+        };
+        let destination = self.cpd.join("src").join("solutions").join(name);
+        let Some(sourcefile) = self.sourcefile(&destination, source)? else {
+            return Ok(());
+        };
+
+        let verdict = verdict_from_polygon_tag(tag).unwrap_or_else(|| {
+            self.warn(format!(
+                "{name} has the unknown tag {tag}, it is imported as rejected"
+            ));
+            ExpectedVerdict::Preset(Preset::Rejected)
+        });
+        if verdict == ExpectedVerdict::Preset(Preset::Main) {
+            self.config.main_solution = Some(self.config.solutions.len());
+        }
+        self.config.solutions.push(Solution {
+            sourcefile,
+            verdict,
+            score: None,
+        });
+        Ok(())
+    }
+
+    /// Reads the file of a `source` element to write it at `destination`, and sets up
+    /// its toolchain from its Polygon type, or from its extension if the type is unknown.
+    fn sourcefile(
+        &mut self,
+        destination: &Path,
+        source: Node,
+    ) -> Result<Option<SourceFile>, Box<dyn Error>> {
+        let path = source.attribute("path").unwrap_or_default();
+        let Some(content) = self.package.read(path)? else {
+            self.warn(format!("{path} is missing"));
+            return Ok(None);
+        };
+
+        let kind = source.attribute("type").unwrap_or_default();
+        let language = language_from_polygon_type(kind);
+        if language.is_none() && !kind.is_empty() {
+            self.warn(format!("{path} has the unknown type {kind}"));
+        }
+        match SourceFile::with_language(destination, self.settings, language.map(|l| l.name())) {
+            Ok(sourcefile) => {
+                self.files.push((destination.to_path_buf(), content));
+                Ok(Some(sourcefile))
+            }
+            Err(e) => {
+                self.warn(format!("{path} was ignored: {e}"));
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// The language code of a Polygon language name, such as "en" for "english".
+fn language_code(name: &str) -> Option<String> {
+    POLYGON_LANGUAGES
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(code, _)| code.to_string())
+        .or_else(|| is_valid_language(name).then(|| name.to_string()))
+}
+
+/// The file name of a path in the package, or `None` if it has none, as an empty path.
+fn package_file_name(path: &str) -> Option<&str> {
+    Path::new(path).file_name().and_then(|name| name.to_str())
+}
+
+/// Expands a path pattern such as "tests/%02d" for the test `n`.
+fn test_path(pattern: &str, n: usize) -> String {
+    let Some(start) = pattern.find('%') else {
+        return pattern.to_string();
+    };
+    let Some(end) = pattern[start..].find('d').map(|end| start + end) else {
+        return pattern.to_string();
+    };
+    let width: usize = pattern[start + 1..end]
+        .trim_start_matches('0')
+        .parse()
+        .unwrap_or(0);
+    format!("{}{n:0width$}{}", &pattern[..start], &pattern[end + 1..])
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)?.text().map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::TempDir;
+    use Verdict::*;
+
+    #[test]
+    fn tags_verdicts() {
+        let tag = |verdict| polygon_tag(&verdict);
+        assert_eq!(tag(ExpectedVerdict::Preset(Preset::Main)), Some("main"));
+        assert_eq!(
+            tag(ExpectedVerdict::Preset(Preset::Correct)),
+            Some("accepted")
+        );
+        assert_eq!(tag(ExpectedVerdict::Verdict(RE)), Some("failed"));
+        assert_eq!(tag(ExpectedVerdict::Verdict(FAIL)), None);
+        assert_eq!(
+            tag(ExpectedVerdict::AnyOf(vec![AC, TLE, AC])),
+            Some("time-limit-exceeded-or-accepted")
+        );
+        assert_eq!(
+            tag(ExpectedVerdict::AnyOf(vec![MLE, TLE])),
+            Some("time-limit-exceeded-or-memory-limit-exceeded")
+        );
+        assert_eq!(tag(ExpectedVerdict::AnyOf(vec![WA])), Some("wrong-answer"));
+        assert_eq!(tag(ExpectedVerdict::AnyOf(vec![WA, TLE])), None);
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        for tag in [
+            "main",
+            "accepted",
+            "rejected",
+            "wrong-answer",
+            "time-limit-exceeded",
+            "memory-limit-exceeded",
+            "failed",
+            "presentation-error",
+            "time-limit-exceeded-or-accepted",
+            "time-limit-exceeded-or-memory-limit-exceeded",
+        ] {
+            let verdict = verdict_from_polygon_tag(tag).unwrap();
+            assert_eq!(polygon_tag(&verdict), Some(tag));
+        }
+        assert_eq!(verdict_from_polygon_tag("idleness-limit-exceeded"), None);
+    }
+
+    #[test]
+    fn expands_test_paths() {
+        assert_eq!(test_path("tests/%02d", 3), "tests/03");
+        assert_eq!(test_path("tests/%d.a", 12), "tests/12.a");
+    }
+
+    #[test]
+    fn imports_into_a_relative_directory() {
+        let package = TempDir::new("import-package");
+        create_dir_all(package.path().join("tests")).unwrap();
+        write(
+            package.path().join("problem.xml"),
+            r#"<problem short-name="sum"><judging><testset name="tests">
+                <tests><test method="manual"/></tests>
+            </testset></judging></problem>"#,
+        )
+        .unwrap();
+        write(package.path().join("tests/01"), "1 2\n").unwrap();
+        write(package.path().join("tests/01.a"), "3\n").unwrap();
+
+        let relative = PathBuf::from(format!("target/rustygon-import-{}", std::process::id()));
+        let problem = TempDir::at(relative.clone());
+        let (cpd, warnings) =
+            import_polygon(package.path(), Some(&relative), &Config::default()).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(cpd, current_dir().unwrap().join(&relative));
+
+        let file = File::open(problem.path().join("problem_config.json")).unwrap();
+        let config = ProblemConfig::from_file(file).unwrap();
+        let testcase = &config.testcases[0];
+        assert!(testcase.input_path.is_absolute());
+        assert_eq!(read(&testcase.input_path).unwrap(), b"1 2\n");
+        assert_eq!(read(&testcase.output_path).unwrap(), b"3\n");
+    }
+}
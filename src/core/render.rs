@@ -0,0 +1,396 @@
+use super::statement::{read_sections, Section, Statement};
+use super::tutorial::read_tutorial;
+use super::ProblemConfig;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::{read_to_string, write};
+use std::path::Path;
+
+/// A sample testcase as shown in the statement.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub input: String,
+    pub output: String,
+}
+
+/// Reads the input and output of every sample testcase, in order.
+pub fn read_samples(config: &ProblemConfig) -> Result<Vec<Sample>, Box<dyn Error>> {
+    let mut samples = Vec::new();
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        if !testcase.sample {
+            continue;
+        }
+        if !testcase.output_path.exists() {
+            return Err(
+                format!("Sample {} has no output. Generate the outputs first", i + 1).into(),
+            );
+        }
+
+        samples.push(Sample {
+            input: read_to_string(&testcase.input_path)?,
+            output: read_to_string(&testcase.output_path)?,
+        });
+    }
+    Ok(samples)
+}
+
+/// Renders a statement of the problem at `cpd` as a standalone HTML page,
+/// with its title, limits, sections and samples. Math is typeset by MathJax.
+pub fn render_html(
+    cpd: &Path,
+    config: &ProblemConfig,
+    statement: &Statement,
+) -> Result<String, Box<dyn Error>> {
+    let sections = read_sections(cpd, &statement.language)?;
+    let samples = read_samples(config)?;
+
+    let mut page = String::new();
+    write_html_head(&mut page, &config.title, &statement.language)?;
+    writeln!(page, "<h1>{}</h1>", escape_html(&config.title))?;
+    writeln!(page, "<div class=\"limits\">")?;
+    writeln!(
+        page,
+        "<div>time limit per test: {}</div>",
+        time_limit(config.time)
+    )?;
+    writeln!(
+        page,
+        "<div>memory limit per test: {} megabytes</div>",
+        config.memory
+    )?;
+    writeln!(page, "</div>")?;
+
+    for (section, text) in &sections {
+        if *section == Section::Notes {
+            write_samples(&mut page, &samples)?;
+        }
+        write_section(&mut page, *section, text)?;
+    }
+    if !sections
+        .iter()
+        .any(|(section, _)| *section == Section::Notes)
+    {
+        write_samples(&mut page, &samples)?;
+    }
+
+    writeln!(page, "</body>")?;
+    writeln!(page, "</html>")?;
+    Ok(page)
+}
+
+/// Renders a statement of the problem at `cpd` as a LaTeX document using the
+/// `problem` environment of olymp.sty, which must be available to build it.
+/// The samples are read from the files written by [`write_examples`].
+pub fn render_latex(
+    cpd: &Path,
+    config: &ProblemConfig,
+    statement: &Statement,
+) -> Result<String, Box<dyn Error>> {
+    let sections = read_sections(cpd, &statement.language)?;
+    let samples = read_samples(config)?;
+
+    let mut document = String::new();
+    write_latex_preamble(&mut document)?;
+    writeln!(
+        document,
+        "\\begin{{problem}}{{{}}}{{standard input}}{{standard output}}{{{}}}{{{} megabytes}}",
+        escape_latex(&config.title),
+        time_limit(config.time),
+        config.memory
+    )?;
+    writeln!(document)?;
+
+    for (section, text) in &sections {
+        if *section == Section::Notes {
+            write_latex_samples(&mut document, &samples)?;
+        }
+        if let Some(command) = latex_section_command(*section) {
+            writeln!(document, "{command}")?;
+        }
+        document.push_str(&markdown_to_latex(text));
+    }
+    if !sections
+        .iter()
+        .any(|(section, _)| *section == Section::Notes)
+    {
+        write_latex_samples(&mut document, &samples)?;
+    }
+
+    writeln!(document, "\\end{{problem}}")?;
+    writeln!(document)?;
+    writeln!(document, "\\end{{document}}")?;
+    Ok(document)
+}
+
+/// Renders the tutorial of the problem at `cpd` in `language` as a standalone HTML page.
+pub fn render_tutorial_html(
+    cpd: &Path,
+    config: &ProblemConfig,
+    language: &str,
+) -> Result<String, Box<dyn Error>> {
+    let tutorial = read_tutorial(cpd, config, language)?;
+
+    let mut page = String::new();
+    write_html_head(&mut page, &config.title, language)?;
+    writeln!(page, "<h1>{}</h1>", escape_html(&config.title))?;
+    page.push_str(&markdown_to_html(&tutorial));
+    writeln!(page, "</body>")?;
+    writeln!(page, "</html>")?;
+    Ok(page)
+}
+
+/// Renders the tutorial of the problem at `cpd` in `language` as a LaTeX document
+/// using the `tutorial` environment of olymp.sty.
+pub fn render_tutorial_latex(
+    cpd: &Path,
+    config: &ProblemConfig,
+    language: &str,
+) -> Result<String, Box<dyn Error>> {
+    let tutorial = read_tutorial(cpd, config, language)?;
+
+    let mut document = String::new();
+    write_latex_preamble(&mut document)?;
+    writeln!(
+        document,
+        "\\begin{{tutorial}}{{{}}}",
+        escape_latex(&config.title)
+    )?;
+    writeln!(document)?;
+    document.push_str(&markdown_to_latex(&tutorial));
+    writeln!(document, "\\end{{tutorial}}")?;
+    writeln!(document)?;
+    writeln!(document, "\\end{{document}}")?;
+    Ok(document)
+}
+
+/// The languages written from right to left.
+const RTL_LANGUAGES: [&str; 4] = ["ar", "fa", "he", "ur"];
+
+const MATHJAX: &str = "https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-chtml.js";
+
+const STYLE: &str = "
+body { max-width: 50em; margin: 2em auto; font-family: serif; line-height: 1.5; }
+h1 { text-align: center; margin-bottom: 0.2em; }
+.limits { text-align: center; font-size: 0.9em; margin-bottom: 2em; }
+.sample { display: flex; gap: 1em; margin-bottom: 1em; }
+.sample div { flex: 1; }
+pre { border: 1px solid #888; padding: 0.5em; margin: 0; overflow-x: auto; }
+";
+
+/// Writes everything up to the opening body tag.
+fn write_html_head(page: &mut String, title: &str, language: &str) -> std::fmt::Result {
+    let direction = if RTL_LANGUAGES.contains(&language) {
+        "rtl"
+    } else {
+        "ltr"
+    };
+    writeln!(page, "<!DOCTYPE html>")?;
+    writeln!(
+        page,
+        "<html lang=\"{}\" dir=\"{direction}\">",
+        escape_html(language)
+    )?;
+    writeln!(page, "<head>")?;
+    writeln!(page, "<meta charset=\"utf-8\">")?;
+    writeln!(page, "<title>{}</title>", escape_html(title))?;
+    writeln!(page, "<style>{STYLE}</style>")?;
+    writeln!(page, "<script src=\"{MATHJAX}\" async></script>")?;
+    writeln!(page, "</head>")?;
+    writeln!(page, "<body>")
+}
+
+fn write_section(page: &mut String, section: Section, text: &str) -> std::fmt::Result {
+    writeln!(page, "<div class=\"{}\">", section.name())?;
+    if let Some(title) = section.title() {
+        writeln!(page, "<h2>{title}</h2>")?;
+    }
+    page.push_str(&markdown_to_html(text));
+    writeln!(page, "</div>")
+}
+
+fn write_samples(page: &mut String, samples: &[Sample]) -> std::fmt::Result {
+    if samples.is_empty() {
+        return Ok(());
+    }
+
+    let title = if samples.len() == 1 {
+        "Example"
+    } else {
+        "Examples"
+    };
+    writeln!(page, "<div class=\"examples\">")?;
+    writeln!(page, "<h2>{title}</h2>")?;
+    for sample in samples {
+        writeln!(page, "<div class=\"sample\">")?;
+        for (name, text) in [("input", &sample.input), ("output", &sample.output)] {
+            writeln!(page, "<div>{name}<pre>{}</pre></div>", escape_html(text))?;
+        }
+        writeln!(page, "</div>")?;
+    }
+    writeln!(page, "</div>")
+}
+
+/// Converts Markdown to HTML, keeping the math between `$` or `$$` for MathJax.
+fn markdown_to_html(text: &str) -> String {
+    let parser = Parser::new_ext(text, Options::ENABLE_TABLES | Options::ENABLE_MATH).map(
+        |event| match event {
+            Event::InlineMath(math) => {
+                Event::InlineHtml(CowStr::from(format!("\\({}\\)", escape_html(&math))))
+            }
+            Event::DisplayMath(math) => {
+                Event::InlineHtml(CowStr::from(format!("\\[{}\\]", escape_html(&math))))
+            }
+            event => event,
+        },
+    );
+
+    let mut result = String::new();
+    html::push_html(&mut result, parser);
+    result
+}
+
+/// Writes everything up to the beginning of the document.
+fn write_latex_preamble(document: &mut String) -> std::fmt::Result {
+    writeln!(document, "\\documentclass[11pt,a4paper,oneside]{{article}}")?;
+    writeln!(document, "\\usepackage[utf8]{{inputenc}}")?;
+    writeln!(document, "\\usepackage{{olymp}}")?;
+    writeln!(document, "\\begin{{document}}")?;
+    writeln!(document)
+}
+
+/// The olymp.sty command starting a section, if the section has a title.
+fn latex_section_command(section: Section) -> Option<&'static str> {
+    match section {
+        Section::Legend => None,
+        Section::Input => Some("\\InputFile"),
+        Section::Output => Some("\\OutputFile"),
+        Section::Scoring => Some("\\Scoring"),
+        Section::Notes => Some("\\Note"),
+    }
+}
+
+/// Writes the samples as `exmpfile` blocks. The samples are read from the files written
+/// by [`write_examples`] next to the document, since olymp.sty typesets the arguments of
+/// `exmp` as LaTeX, where characters such as `%`, `_` or `\\` would break the document.
+fn write_latex_samples(document: &mut String, samples: &[Sample]) -> std::fmt::Result {
+    if samples.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(document, "\\Examples")?;
+    writeln!(document)?;
+    writeln!(document, "\\begin{{example}}")?;
+    for n in 1..=samples.len() {
+        let example = example_name(n);
+        writeln!(document, "\\exmpfile{{{example}}}{{{example}.a}}%")?;
+    }
+    writeln!(document, "\\end{{example}}")?;
+    writeln!(document)
+}
+
+/// The file holding the input of the `n`th sample (1-based) for a LaTeX statement,
+/// such as "example.01". The output is held by the same file with ".a" appended.
+pub fn example_name(n: usize) -> String {
+    format!("example.{n:02}")
+}
+
+/// Writes the sample files a LaTeX statement reads to `dir`.
+pub fn write_examples(dir: &Path, config: &ProblemConfig) -> Result<(), Box<dyn Error>> {
+    for (i, sample) in read_samples(config)?.iter().enumerate() {
+        let example = example_name(i + 1);
+        write(dir.join(&example), &sample.input)?;
+        write(dir.join(format!("{example}.a")), &sample.output)?;
+    }
+    Ok(())
+}
+
+/// Converts Markdown to LaTeX, keeping the math between `$` or `$$` as it is.
+/// HTML in the Markdown is dropped.
+pub fn markdown_to_latex(text: &str) -> String {
+    let mut result = String::new();
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(text, Options::ENABLE_MATH) {
+        match event {
+            Event::Start(tag) => result.push_str(match tag {
+                Tag::Heading { .. } => "\\subsection*{",
+                Tag::BlockQuote(_) => "\\begin{quote}\n",
+                Tag::CodeBlock(_) => {
+                    in_code_block = true;
+                    "\\begin{verbatim}\n"
+                }
+                Tag::List(Some(_)) => "\\begin{enumerate}\n",
+                Tag::List(None) => "\\begin{itemize}\n",
+                Tag::Item => "\\item ",
+                Tag::Emphasis => "\\emph{",
+                Tag::Strong => "\\textbf{",
+                _ => "",
+            }),
+            Event::End(tag) => result.push_str(match tag {
+                TagEnd::Paragraph => "\n\n",
+                TagEnd::Heading(_) => "}\n\n",
+                TagEnd::BlockQuote(_) => "\\end{quote}\n\n",
+                TagEnd::CodeBlock => {
+                    in_code_block = false;
+                    "\\end{verbatim}\n\n"
+                }
+                TagEnd::List(true) => "\\end{enumerate}\n\n",
+                TagEnd::List(false) => "\\end{itemize}\n\n",
+                TagEnd::Item => "\n",
+                TagEnd::Emphasis | TagEnd::Strong => "}",
+                _ => "",
+            }),
+            Event::Text(text) if in_code_block => result.push_str(&text),
+            Event::Text(text) => result.push_str(&escape_latex(&text)),
+            Event::Code(code) => write!(result, "\\texttt{{{}}}", escape_latex(&code)).unwrap(),
+            Event::InlineMath(math) => write!(result, "${math}$").unwrap(),
+            Event::DisplayMath(math) => write!(result, "$${math}$$").unwrap(),
+            Event::SoftBreak => result.push('\n'),
+            Event::HardBreak => result.push_str("\\\\\n"),
+            Event::Rule => result.push_str("\\medskip\\hrule\\medskip\n\n"),
+            _ => {}
+        }
+    }
+    result
+}
+
+pub fn escape_latex(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => result.push_str("\\textbackslash{}"),
+            '~' => result.push_str("\\textasciitilde{}"),
+            '^' => result.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn time_limit(time: f32) -> String {
+    if time == 1.0 {
+        "1 second".to_string()
+    } else {
+        format!("{time} seconds")
+    }
+}
+
+/// Escapes text for HTML or XML, inside elements and double-quoted attributes.
+pub fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
@@ -1,21 +1,180 @@
+use super::config::Config;
 use super::source::SourceFile;
 use super::{modify_config, GenericResult};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs::{copy, File};
 use std::path::Path;
+use std::str::FromStr;
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, clap::ValueEnum)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 pub enum Verdict {
     #[default]
     AC,
     TLE,
     WA,
+    MLE,
+    RE,
+    PE,
+    /// The checker failed, which is an error in the problem rather than the solution.
+    #[value(skip)]
+    FAIL,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Verdict::AC => "AC",
+            Verdict::TLE => "TLE",
+            Verdict::WA => "WA",
+            Verdict::MLE => "MLE",
+            Verdict::RE => "RE",
+            Verdict::PE => "PE",
+            Verdict::FAIL => "FAIL",
+        };
+        f.pad(name)
+    }
+}
+
+/// A named group of verdicts a solution may be tagged with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// The model solution of the problem. Must pass every test.
+    Main,
+    /// Must pass every test.
+    Correct,
+    /// Must fail at least one test in any way.
+    Rejected,
+    /// Must crash on at least one test.
+    Failed,
+    /// May exceed the time limit on any test.
+    TimeLimitOrAccepted,
+    /// Must exceed the time or memory limit on at least one test.
+    TimeLimitOrMemoryLimit,
+}
+
+impl Preset {
+    const ALL: [Preset; 6] = [
+        Preset::Main,
+        Preset::Correct,
+        Preset::Rejected,
+        Preset::Failed,
+        Preset::TimeLimitOrAccepted,
+        Preset::TimeLimitOrMemoryLimit,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Preset::Main => "main",
+            Preset::Correct => "correct",
+            Preset::Rejected => "rejected",
+            Preset::Failed => "failed",
+            Preset::TimeLimitOrAccepted => "time-limit-or-accepted",
+            Preset::TimeLimitOrMemoryLimit => "time-limit-or-memory-limit",
+        }
+    }
+
+    fn verdicts(&self) -> Vec<Verdict> {
+        use Verdict::*;
+        match self {
+            Preset::Main | Preset::Correct => vec![AC],
+            Preset::Rejected => vec![TLE, WA, MLE, RE, PE],
+            Preset::Failed => vec![RE],
+            Preset::TimeLimitOrAccepted => vec![TLE, AC],
+            Preset::TimeLimitOrMemoryLimit => vec![TLE, MLE],
+        }
+    }
+}
+
+/// The verdicts a solution is expected to get over all the testcases.
+///
+/// Deserializes from a preset name ("rejected"), a single verdict ("WA")
+/// or a list of verdicts (["TLE", "AC"]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExpectedVerdict {
+    Preset(Preset),
+    Verdict(Verdict),
+    AnyOf(Vec<Verdict>),
+}
+
+impl Default for ExpectedVerdict {
+    fn default() -> Self {
+        ExpectedVerdict::Verdict(Verdict::AC)
+    }
+}
+
+impl ExpectedVerdict {
+    /// The verdicts the solution may get on a test, besides AC which it may always get.
+    /// AC among them means the solution may pass every test, see [`ExpectedVerdict::accepts`].
+    pub fn verdicts(&self) -> Vec<Verdict> {
+        match self {
+            ExpectedVerdict::Preset(preset) => preset.verdicts(),
+            ExpectedVerdict::Verdict(verdict) => vec![*verdict],
+            ExpectedVerdict::AnyOf(verdicts) => verdicts.clone(),
+        }
+    }
+
+    /// Checks the verdicts a solution got on every test against `self`.
+    ///
+    /// Every test must get AC or one of the expected verdicts. Unless AC is
+    /// itself expected, at least one test must get a verdict other than AC.
+    pub fn accepts(&self, observed: &[Verdict]) -> bool {
+        let expected = self.verdicts();
+        let allowed = observed
+            .iter()
+            .all(|v| *v == Verdict::AC || expected.contains(v));
+
+        allowed && (expected.contains(&Verdict::AC) || observed.iter().any(|v| *v != Verdict::AC))
+    }
+}
+
+impl fmt::Display for ExpectedVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectedVerdict::Preset(preset) => f.pad(preset.name()),
+            ExpectedVerdict::Verdict(verdict) => verdict.fmt(f),
+            ExpectedVerdict::AnyOf(verdicts) => {
+                let names: Vec<String> = verdicts.iter().map(Verdict::to_string).collect();
+                f.pad(&names.join("|"))
+            }
+        }
+    }
+}
+
+/// Parses a preset name, or verdicts separated by '|' such as "TLE|AC".
+impl FromStr for ExpectedVerdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(preset) = Preset::ALL
+            .iter()
+            .find(|p| p.name().eq_ignore_ascii_case(s))
+        {
+            return Ok(ExpectedVerdict::Preset(*preset));
+        }
+
+        let verdicts = s
+            .split('|')
+            .map(|v| Verdict::from_str(v.trim(), true))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match verdicts.as_slice() {
+            [verdict] => Ok(ExpectedVerdict::Verdict(*verdict)),
+            _ => Ok(ExpectedVerdict::AnyOf(verdicts)),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Solution {
     pub sourcefile: SourceFile,
-    pub verdict: Verdict,
+    pub verdict: ExpectedVerdict,
+    /// The total points the solution is expected to score over the test groups.
+    #[serde(default)]
+    pub score: Option<f64>,
 }
 
 /// Adds a solution file to the problem.
@@ -27,14 +186,20 @@ pub struct Solution {
 /// * `name` - The name of the source file we add
 /// * `from` - if not None. the content of the solution file will be copied from this file.
 /// * `verdict` - The expected verdict of the solution.
+/// * `settings` - The config providing the toolchains.
+/// * `lang` - if not None. the profile or language of the solution, instead of guessing it.
 pub fn add_solution(
     cpd: &Path,
     name: &str,
     from: Option<&Path>,
-    verdict: Verdict,
+    verdict: ExpectedVerdict,
+    settings: &Config,
+    lang: Option<&str>,
 ) -> GenericResult {
     modify_config(cpd, |config| {
         let source_path = cpd.join("src/solutions").join(name);
+        let sourcefile = SourceFile::with_language(&source_path, settings, lang)?;
+
         if let Some(path) = from {
             copy(path, &source_path)?;
         } else {
@@ -42,8 +207,9 @@ pub fn add_solution(
         }
 
         config.solutions.push(Solution {
-            sourcefile: SourceFile::from_filename(&source_path),
+            sourcefile,
             verdict: verdict.clone(),
+            score: None,
         });
 
         Ok(())
@@ -51,7 +217,7 @@ pub fn add_solution(
 }
 
 pub fn remove_solution(cpd: &Path, name: &str) -> GenericResult {
-    return modify_config(cpd, |config| {
+    modify_config(cpd, |config| {
         let pos = config
             .solutions
             .iter()
@@ -59,8 +225,190 @@ pub fn remove_solution(cpd: &Path, name: &str) -> GenericResult {
             .ok_or(format!("{name} was not found in problem_config.json"))?;
 
         config.solutions.remove(pos);
+        config.main_solution = match config.main_solution {
+            Some(main) if main == pos => None,
+            Some(main) if main > pos => Some(main - 1),
+            main => main,
+        };
         std::fs::remove_file(cpd.join("src/solutions/").join(name))?;
 
         Ok(())
-    });
+    })
+}
+
+/// Sets the total points the solution `name` is expected to score, or clears it.
+pub fn set_expected_score(cpd: &Path, name: &str, score: Option<f64>) -> GenericResult {
+    modify_config(cpd, |config| {
+        let solution = config
+            .solutions
+            .iter_mut()
+            .find(|x| x.sourcefile.source.file_name().unwrap().eq(name))
+            .ok_or(format!("{name} was not found in problem_config.json"))?;
+
+        solution.score = score;
+        Ok(())
+    })
+}
+
+/// Sets the main solution of the problem, which is used to generate the test outputs.
+///
+/// The solution must be expected to pass every test. Its expected verdict becomes the
+/// "main" preset, and the one of the previous main solution becomes "correct".
+pub fn set_main_solution(cpd: &Path, name: &str) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .solutions
+            .iter()
+            .position(|x| x.sourcefile.source.file_name().unwrap().eq(name))
+            .ok_or(format!("{name} was not found in problem_config.json"))?;
+
+        let verdict = &config.solutions[pos].verdict;
+        if verdict.verdicts() != [Verdict::AC] {
+            return Err(format!(
+                "{name} is expected to get {verdict}, it cannot be the main solution"
+            )
+            .into());
+        }
+        if let Some(main) = config.main_solution {
+            config.solutions[main].verdict = ExpectedVerdict::Preset(Preset::Correct);
+        }
+        config.solutions[pos].verdict = ExpectedVerdict::Preset(Preset::Main);
+        config.main_solution = Some(pos);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::temp_problem;
+    use crate::core::ProblemConfig;
+    use Verdict::*;
+
+    #[test]
+    fn parses_expected_verdicts() {
+        assert_eq!("main".parse(), Ok(ExpectedVerdict::Preset(Preset::Main)));
+        assert_eq!(
+            "Time-Limit-Or-Accepted".parse(),
+            Ok(ExpectedVerdict::Preset(Preset::TimeLimitOrAccepted))
+        );
+        assert_eq!("wa".parse(), Ok(ExpectedVerdict::Verdict(WA)));
+        assert_eq!(
+            "tle | AC".parse(),
+            Ok(ExpectedVerdict::AnyOf(vec![TLE, AC]))
+        );
+        assert!("slow".parse::<ExpectedVerdict>().is_err());
+        assert!("wa|".parse::<ExpectedVerdict>().is_err());
+        // FAIL is an error in the problem, which no solution is expected to get.
+        assert!("fail".parse::<ExpectedVerdict>().is_err());
+    }
+
+    #[test]
+    fn accepts_single_verdicts() {
+        let ac = ExpectedVerdict::Verdict(AC);
+        assert!(ac.accepts(&[AC, AC]));
+        assert!(!ac.accepts(&[AC, WA]));
+
+        let wa = ExpectedVerdict::Verdict(WA);
+        assert!(wa.accepts(&[AC, WA]));
+        assert!(!wa.accepts(&[AC, AC]));
+        assert!(!wa.accepts(&[WA, TLE]));
+    }
+
+    #[test]
+    fn accepts_any_of_verdicts() {
+        let tle_or_ac = ExpectedVerdict::AnyOf(vec![TLE, AC]);
+        assert!(tle_or_ac.accepts(&[AC, AC]));
+        assert!(tle_or_ac.accepts(&[TLE, AC]));
+        assert!(!tle_or_ac.accepts(&[TLE, WA]));
+
+        let tle_or_mle = ExpectedVerdict::AnyOf(vec![TLE, MLE]);
+        assert!(tle_or_mle.accepts(&[AC, MLE, TLE]));
+        assert!(!tle_or_mle.accepts(&[AC, AC]));
+    }
+
+    #[test]
+    fn accepts_presets() {
+        let rejected = ExpectedVerdict::Preset(Preset::Rejected);
+        assert!(rejected.accepts(&[AC, RE, WA]));
+        assert!(!rejected.accepts(&[AC, AC]));
+        assert!(!rejected.accepts(&[FAIL]));
+
+        let failed = ExpectedVerdict::Preset(Preset::Failed);
+        assert!(failed.accepts(&[AC, RE]));
+        assert!(!failed.accepts(&[WA]));
+
+        let main = ExpectedVerdict::Preset(Preset::Main);
+        assert!(main.accepts(&[AC, AC]));
+        assert!(!main.accepts(&[AC, TLE]));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let cases = [
+            (ExpectedVerdict::Preset(Preset::Main), r#""main""#),
+            (
+                ExpectedVerdict::Preset(Preset::TimeLimitOrMemoryLimit),
+                r#""time-limit-or-memory-limit""#,
+            ),
+            (ExpectedVerdict::Verdict(WA), r#""WA""#),
+            (ExpectedVerdict::AnyOf(vec![TLE, AC]), r#"["TLE","AC"]"#),
+        ];
+        for (verdict, json) in cases {
+            assert_eq!(serde_json::to_string(&verdict).unwrap(), json);
+            assert_eq!(
+                serde_json::from_str::<ExpectedVerdict>(json).unwrap(),
+                verdict
+            );
+        }
+    }
+
+    #[test]
+    fn replaces_the_main_solution() {
+        let problem = temp_problem("main-solution", 1.0, 256);
+        let cpd = problem.path();
+        let solution = |name: &str, verdict: ExpectedVerdict| Solution {
+            sourcefile: SourceFile {
+                source: cpd.join("src/solutions").join(name),
+                ..Default::default()
+            },
+            verdict,
+            score: None,
+        };
+        modify_config(cpd, |config| {
+            config.solutions = vec![
+                solution("ok.cpp", ExpectedVerdict::Verdict(AC)),
+                solution("also_ok.cpp", ExpectedVerdict::Preset(Preset::Correct)),
+                solution("wa.cpp", ExpectedVerdict::Verdict(WA)),
+            ];
+            Ok(())
+        })
+        .unwrap();
+        let verdicts = || {
+            let file = File::open(cpd.join("problem_config.json")).unwrap();
+            let config = ProblemConfig::from_file(file).unwrap();
+            let verdicts: Vec<String> = config
+                .solutions
+                .iter()
+                .map(|solution| solution.verdict.to_string())
+                .collect();
+            (config.main_solution, verdicts)
+        };
+
+        set_main_solution(cpd, "ok.cpp").unwrap();
+        assert_eq!(
+            verdicts(),
+            (Some(0), vec!["main".into(), "correct".into(), "WA".into()])
+        );
+        set_main_solution(cpd, "also_ok.cpp").unwrap();
+        assert_eq!(
+            verdicts(),
+            (Some(1), vec!["correct".into(), "main".into(), "WA".into()])
+        );
+        assert!(set_main_solution(cpd, "wa.cpp").is_err());
+        assert_eq!(
+            verdicts(),
+            (Some(1), vec!["correct".into(), "main".into(), "WA".into()])
+        );
+    }
 }
@@ -1,50 +1,177 @@
-use super::{modify_config, GenericResult};
+use super::checker::Checker;
+use super::config::Config;
+use super::language::Toolchain;
+use super::solution::Verdict;
+use super::{modify_config, GenericResult, ProblemConfig};
 use bstr::ByteSlice;
-use std::ffi::OsString;
+use std::env::var_os;
+use std::ffi::{CStr, OsString};
+use std::fmt;
 use std::io;
-use std::process::ExitStatus;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Child, Command, ExitStatus};
 use serde::{Deserialize, Serialize};
 use std::fs::{copy, File};
 use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SourceFile {
     pub source: PathBuf,
+    /// The compiler, empty if the source is not compiled.
     pub compiler: PathBuf,
     pub compiler_args: Vec<String>,
+    /// The compiled binary inside "{cpd}/bin", or for an interpreted
+    /// source the command that runs it, such as "python3".
     pub bin: PathBuf,
-    pub bin_args: Vec<String>
+    pub bin_args: Vec<String>,
+    #[serde(default)]
+    pub interpreted: bool,
 }
 
 impl SourceFile {
-    pub fn from_filename(filename: &Path) -> Self {
-        match filename.extension().map(|ext| ext.to_str().unwrap()) {
-            Some("cpp") => Self {
-                source: filename.to_path_buf(),
-                compiler: "g++".into(),
-                compiler_args: vec!["%source%".into(), "-o".into(), "%bin%".into()],
-                bin: filename.with_extension("exe").file_name().unwrap().into(),
-                bin_args: vec![]
-            },
-            None | Some(_) => Self::default()
+    /// Sets up the toolchain of a source from its extension, following
+    /// the extensions and profiles of `config`.
+    /// Returns `None` if the language of the source is unknown.
+    pub fn from_filename(filename: &Path, config: &Config) -> Option<Self> {
+        config
+            .toolchain_for(filename)
+            .map(|toolchain| Self::from_toolchain(filename, &toolchain))
+    }
+
+    /// Sets up the toolchain of a source from the profile or language named `lang`,
+    /// or from its extension if `lang` is `None`.
+    pub fn with_language(
+        filename: &Path,
+        config: &Config,
+        lang: Option<&str>,
+    ) -> Result<Self, String> {
+        match lang {
+            Some(lang) => config
+                .toolchain(lang)
+                .map(|toolchain| Self::from_toolchain(filename, &toolchain))
+                .ok_or(format!("{lang} is neither a profile nor a language")),
+            None => Self::from_filename(filename, config)
+                .ok_or(format!("The language of {} is unknown", filename.display())),
         }
     }
 
-    pub fn build(&self, cpd: &Path) -> std::io::Result<()>  {
-        let bin_path = cpd.join("bin").join(&self.bin);
+    pub fn from_toolchain(filename: &Path, toolchain: &Toolchain) -> Self {
+        let (bin, bin_args) = match &toolchain.interpreter {
+            Some(interpreter) => (interpreter.into(), toolchain.interpreter_args.clone()),
+            None => {
+                let mut bin = filename.file_name().unwrap().to_os_string();
+                bin.push(".exe");
+                (bin.into(), vec![])
+            }
+        };
 
-        if !more_recent(&self.source, &bin_path).unwrap() {
-            print!("Source is more recent than binary. Skipping...");
-            return Ok(());
+        Self {
+            source: filename.to_path_buf(),
+            compiler: toolchain.compiler.clone().unwrap_or_default().into(),
+            compiler_args: toolchain.compiler_args.clone(),
+            bin,
+            bin_args,
+            interpreted: toolchain.interpreter.is_some(),
         }
+    }
 
-        let args = self.compiler_args.iter().map(|s| {
-            match s.as_str() {
-                "%source%" => OsString::from(&self.source),
-                "%bin%" => OsString::from(&bin_path),
-                e => OsString::from(e)
+    /// The path of the compiled binary inside the problem directory.
+    ///
+    /// An interpreted source has no binary, so this is a stamp file created whenever
+    /// it is compiled, or the source itself if it is not compiled at all.
+    pub fn bin_path(&self, cpd: &Path) -> PathBuf {
+        match (self.interpreted, self.compiler.as_os_str().is_empty()) {
+            (false, _) => cpd.join("bin").join(&self.bin),
+            (true, false) => {
+                let mut stamp = self.source.file_name().unwrap().to_os_string();
+                stamp.push(".stamp");
+                cpd.join("bin").join(stamp)
             }
-        });
+            (true, true) => self.source.clone(),
+        }
+    }
+
+    /// Replaces the placeholders anywhere inside `arg`, as in "-o%bin%":
+    ///
+    /// * `%source%` - The path of the source.
+    /// * `%bin%` - The path of the compiled binary.
+    /// * `%dir%` - The directory of the binaries, "{cpd}/bin".
+    /// * `%stem%` - The file name of the source without its extension.
+    /// * `%problem%` - The name of the problem directory.
+    /// * `%time_limit%` - The time limit of the problem in seconds.
+    /// * `%memory_limit%` - The memory limit of the problem in megabytes.
+    /// * `${NAME}` - The environment variable `NAME`, or nothing if it is not set.
+    ///
+    /// Anything else between '%' is left as it is.
+    fn expand(&self, cpd: &Path, arg: &str) -> io::Result<OsString> {
+        let mut expanded = OsString::new();
+        let mut rest = arg;
+
+        while let Some(start) = rest.find(['%', '$']) {
+            expanded.push(&rest[..start]);
+            rest = &rest[start..];
+
+            let env = rest.strip_prefix("${").and_then(|r| r.split_once('}'));
+            let placeholder = match rest.strip_prefix('%').and_then(|r| r.split_once('%')) {
+                Some((name, after)) => self.placeholder(cpd, name)?.map(|value| (value, after)),
+                None => None,
+            };
+
+            if let Some((name, after)) = env {
+                expanded.push(var_os(name).unwrap_or_default());
+                rest = after;
+            } else if let Some((value, after)) = placeholder {
+                expanded.push(value);
+                rest = after;
+            } else {
+                expanded.push(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+
+        expanded.push(rest);
+        Ok(expanded)
+    }
+
+    /// The value of the placeholder `%{name}%`, or `None` if there is no such placeholder.
+    fn placeholder(&self, cpd: &Path, name: &str) -> io::Result<Option<OsString>> {
+        let problem_config = || {
+            let file = File::open(cpd.join("problem_config.json"))?;
+            ProblemConfig::from_file(file).map_err(io::Error::other)
+        };
+
+        Ok(Some(match name {
+            "source" => self.source.clone().into(),
+            "bin" => self.bin_path(cpd).into(),
+            "dir" => cpd.join("bin").into(),
+            "stem" => self.source.file_stem().unwrap_or_default().into(),
+            "problem" => cpd.file_name().unwrap_or_default().into(),
+            "time_limit" => problem_config()?.time.to_string().into(),
+            "memory_limit" => problem_config()?.memory.to_string().into(),
+            _ => return Ok(None),
+        }))
+    }
+
+    pub fn build(&self, cpd: &Path) -> std::io::Result<()> {
+        if self.compiler.as_os_str().is_empty() {
+            print!("Nothing to compile. Skipping...");
+            return Ok(());
+        }
+
+        let bin_path = self.bin_path(cpd);
+
+        if !more_recent(&self.source, &bin_path)? {
+            print!("Binary is up to date. Skipping...");
+            return Ok(());
+        }
+
+        let args = self
+            .compiler_args
+            .iter()
+            .map(|arg| self.expand(cpd, arg))
+            .collect::<io::Result<Vec<_>>>()?;
 
         let output = std::process::Command::new(&self.compiler)
             .args(args)
@@ -57,25 +184,276 @@ impl SourceFile {
             eprintln!("=============");
             eprintln!("stdout: {}", output.stdout.to_str_lossy());
             eprintln!("=============");
+            return Err(io::Error::other(format!(
+                "failed to compile {}",
+                self.source.display()
+            )));
+        }
+        if self.interpreted {
+            File::create(bin_path)?;
         }
         Ok(())
     }
 
-    pub fn run(&self, cpd: &Path) -> io::Result<ExitStatus>{
-        let bin_path = cpd.join("bin").join(&self.bin);
-        Ok(std::process::Command::new(bin_path)
-            .args(self.bin_args.iter())
-            .spawn()?
-            .wait()?)
+    /// Returns a `Command` that executes the compiled binary, or the interpreter of the
+    /// source, with its `bin_args` under the given `limits`.
+    /// The caller is free to append arguments and redirect stdio before spawning.
+    pub fn command(&self, cpd: &Path, limits: &Limits) -> io::Result<Command> {
+        let mut command = match self.interpreted {
+            true => Command::new(&self.bin),
+            false => Command::new(self.bin_path(cpd)),
+        };
+        for arg in &self.bin_args {
+            command.arg(self.expand(cpd, arg)?);
+        }
+        limits.apply(&mut command);
+        Ok(command)
+    }
+
+    /// Runs the program under `limits` and waits for it to finish.
+    /// The arguments and standard streams of the program are taken from `options`.
+    pub fn run(&self, cpd: &Path, limits: &Limits, options: &RunOptions) -> io::Result<RunReport> {
+        let mut command = self.command(cpd, limits)?;
+        command.args(&options.args);
+
+        if let Some(stdin) = &options.stdin {
+            command.stdin(File::open(stdin)?);
+        }
+        if let Some(stdout) = &options.stdout {
+            command.stdout(File::create(stdout)?);
+        }
+        if let Some(stderr) = &options.stderr {
+            command.stderr(File::create(stderr)?);
+        }
+
+        let mut report = wait_child(command.spawn()?, limits)?;
+        report.stdout = options.stdout.clone();
+        report.stderr = options.stderr.clone();
+        Ok(report)
+    }
+}
+
+/// The arguments and standard streams of a program run.
+/// A stream without a path is inherited from rustygon.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Arguments passed after the `bin_args` of the program.
+    pub args: Vec<OsString>,
+    /// The file fed to the standard input of the program.
+    pub stdin: Option<PathBuf>,
+    /// The file the standard output of the program is written to.
+    pub stdout: Option<PathBuf>,
+    /// The file the standard error of the program is written to.
+    pub stderr: Option<PathBuf>,
+}
+
+/// A program is killed once its wall time reaches this multiple of the time limit.
+/// This catches programs that sleep or block on input without using any CPU time.
+const WALL_TIME_FACTOR: u32 = 2;
+
+/// The address space a program may use beyond its memory limit, in megabytes.
+/// A program must be able to outgrow the limit for its peak memory to show it exceeded it;
+/// one that fails to allocate first crashes and is judged by its crash instead.
+const MEMORY_HEADROOM: u64 = 1024;
+
+/// Resource limits enforced on a running program.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Limits {
+    /// The CPU time limit.
+    pub time: Option<Duration>,
+    /// The memory limit in megabytes.
+    pub memory: Option<u32>,
+}
+
+impl Limits {
+    /// Installs the limits as rlimits of the process spawned by `command`.
+    fn apply(&self, command: &mut Command) {
+        let memory = self
+            .memory
+            .map(|mb| (u64::from(mb) + MEMORY_HEADROOM) * 1024 * 1024);
+        // RLIMIT_CPU has a granularity of whole seconds, so round up and let
+        // the measured CPU time decide whether the limit was actually exceeded.
+        let cpu = self.time.map(|time| time.as_secs_f64().ceil() as u64 + 1);
+
+        // SAFETY: the closure only calls setrlimit, which is async-signal-safe.
+        unsafe {
+            command.pre_exec(move || {
+                // RLIMIT_DATA rather than RLIMIT_AS, since the JVM reserves far more
+                // address space than it ever uses and cannot start under RLIMIT_AS.
+                if let Some(bytes) = memory {
+                    set_rlimit(libc::RLIMIT_DATA, bytes)?;
+                }
+                if let Some(seconds) = cpu {
+                    set_rlimit(libc::RLIMIT_CPU, seconds)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// The wall time after which a running program is killed.
+    fn wall_time(&self) -> Option<Duration> {
+        self.time.map(|time| time * WALL_TIME_FACTOR)
+    }
+}
+
+// glibc declares the resources with their own type, while other libcs use a plain int.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type Resource = libc::c_int;
+
+fn set_rlimit(resource: Resource, value: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    match unsafe { libc::setrlimit(resource, &limit) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// How a program run went.
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// The exit code, or `None` if the program was terminated by a signal.
+    pub exit_code: Option<i32>,
+    /// The signal that terminated the program, if any.
+    pub signal: Option<i32>,
+    /// Whether the program was killed for exceeding the wall time guard.
+    pub killed: bool,
+    /// The user and system time used by the program.
+    pub cpu_time: Duration,
+    pub wall_time: Duration,
+    /// The peak resident memory of the program in kilobytes.
+    pub peak_memory: u64,
+    /// The file the standard output was redirected to, if any.
+    pub stdout: Option<PathBuf>,
+    /// The file the standard error was redirected to, if any.
+    pub stderr: Option<PathBuf>,
+}
+
+impl RunReport {
+    /// Whether the program exited normally with a zero exit code.
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    pub fn time_exceeded(&self, limits: &Limits) -> bool {
+        self.killed || limits.time.is_some_and(|time| self.cpu_time > time)
+    }
+
+    /// Whether the peak resident memory of the program went over the memory limit.
+    pub fn memory_exceeded(&self, limits: &Limits) -> bool {
+        limits
+            .memory
+            .is_some_and(|memory| self.peak_memory > u64::from(memory) * 1024)
+    }
+
+    /// Returns how the program crashed, if it did not exit successfully.
+    pub fn crash(&self) -> Option<Crash> {
+        match (self.exit_code, self.signal) {
+            (Some(0), _) => None,
+            (Some(code), _) => Some(Crash::ExitCode(code)),
+            (None, Some(signal)) => Some(Crash::Signal(signal)),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the verdict caused by overrunning `limits`, if any.
+    /// The memory limit comes first, since a program growing into the headroom
+    /// often runs out of time before it runs out of memory.
+    pub fn limit_verdict(&self, limits: &Limits) -> Option<Verdict> {
+        if self.memory_exceeded(limits) {
+            Some(Verdict::MLE)
+        } else if self.time_exceeded(limits) {
+            Some(Verdict::TLE)
+        } else {
+            None
+        }
     }
 }
 
-fn more_recent(source: &Path, binary: &Path) -> io::Result<bool> {
+/// The way a program terminated abnormally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crash {
+    /// The program exited with a non-zero exit code.
+    ExitCode(i32),
+    /// The program was killed by a signal.
+    Signal(i32),
+}
 
-    assert!(source.exists());
+impl fmt::Display for Crash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crash::ExitCode(code) => write!(f, "exit code {code}"),
+            Crash::Signal(signal) => {
+                // SAFETY: strsignal returns a pointer to a static, nul terminated string.
+                let name = unsafe { CStr::from_ptr(libc::strsignal(*signal)) };
+                write!(f, "signal {signal} ({})", name.to_string_lossy())
+            }
+        }
+    }
+}
+
+/// Waits for `child` to exit while collecting its resource usage.
+/// The child is killed if it outlives the wall time guard of `limits`.
+pub fn wait_child(mut child: Child, limits: &Limits) -> io::Result<RunReport> {
+    let start = Instant::now();
+    let pid = child.id() as libc::pid_t;
+    let wall_time = limits.wall_time();
+    let mut killed = false;
+
+    loop {
+        let mut status = 0;
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        let options = if killed { 0 } else { libc::WNOHANG };
+
+        match unsafe { libc::wait4(pid, &mut status, options, &mut usage) } {
+            -1 => return Err(io::Error::last_os_error()),
+            0 => {}
+            _ => {
+                let status = ExitStatus::from_raw(status);
+                return Ok(RunReport {
+                    exit_code: status.code(),
+                    signal: status.signal(),
+                    killed,
+                    cpu_time: timeval_duration(usage.ru_utime) + timeval_duration(usage.ru_stime),
+                    wall_time: start.elapsed(),
+                    peak_memory: usage.ru_maxrss as u64,
+                    stdout: None,
+                    stderr: None,
+                });
+            }
+        }
+
+        if wall_time.is_some_and(|wall| start.elapsed() > wall) {
+            child.kill()?;
+            killed = true;
+        } else {
+            sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+fn timeval_duration(time: libc::timeval) -> Duration {
+    Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+}
+
+/// Whether `source` was modified after `binary`, or `binary` does not exist.
+/// Fails if `source` does not exist.
+pub fn more_recent(source: &Path, binary: &Path) -> io::Result<bool> {
+
+    if !source.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} does not exist", source.display()),
+        ));
+    }
 
     if !binary.exists() {
-        return Ok(false);
+        return Ok(true);
     }
 
     let stime = source.metadata()?.modified()?;
@@ -92,9 +470,18 @@ fn more_recent(source: &Path, binary: &Path) -> io::Result<bool> {
 /// * `cpd`  - The problem directory to which we want to add the source file
 /// * `name` - The name of the source file we add
 /// * `from` - if not None. the content of the source file will be copied from this file.
-pub fn add_source(cpd: &Path, name: &str, from: Option<&Path>) -> GenericResult {
+/// * `settings` - The config providing the toolchains.
+/// * `lang` - if not None. the profile or language of the source, instead of guessing it.
+pub fn add_source(
+    cpd: &Path,
+    name: &str,
+    from: Option<&Path>,
+    settings: &Config,
+    lang: Option<&str>,
+) -> GenericResult {
     modify_config(cpd, |config| {
         let source_path = cpd.join("src/sources").join(name);
+        let sourcefile = SourceFile::with_language(&source_path, settings, lang)?;
 
         if let Some(path) = from {
             copy(path, &source_path)?;
@@ -102,7 +489,7 @@ pub fn add_source(cpd: &Path, name: &str, from: Option<&Path>) -> GenericResult
             File::create_new(&source_path)?;
         }
 
-        config.sources.push(SourceFile::from_filename(&source_path));
+        config.sources.push(sourcefile);
         Ok(())
     })
 }
@@ -116,11 +503,27 @@ pub fn remove_source(cpd: &Path, name: &str) -> GenericResult {
             .ok_or(format!("{name} was not found in problem_config.json"))?;
 
         config.sources.remove(pos);
+        config.validator = shift_index(config.validator, pos);
+        config.interactor = shift_index(config.interactor, pos);
+        config.checker = match config.checker {
+            Some(Checker::Source(checker)) => shift_index(Some(checker), pos).map(Checker::Source),
+            checker => checker,
+        };
         std::fs::remove_file(cpd.join("src/sources/").join(name))?;
         Ok(())
     })
 }
 
+/// Updates the index of a source after the source at `removed` was removed.
+/// The index is cleared if it pointed to the removed source.
+fn shift_index(index: Option<usize>, removed: usize) -> Option<usize> {
+    match index {
+        Some(index) if index == removed => None,
+        Some(index) if index > removed => Some(index - 1),
+        index => index,
+    }
+}
+
 pub fn set_validator(cpd: &Path, name: &str) -> GenericResult {
     modify_config(cpd, |config| {
         let pos = config
@@ -133,3 +536,56 @@ pub fn set_validator(cpd: &Path, name: &str) -> GenericResult {
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::{temp_problem, TempDir};
+    use std::fs::{create_dir, write};
+
+    #[test]
+    fn expands_placeholders() {
+        let problem = temp_problem("expand", 2.5, 128);
+        let cpd = problem.path();
+
+        let source = SourceFile {
+            source: cpd.join("src").join("solutions").join("ok.cpp"),
+            compiler: "g++".into(),
+            bin: "ok.cpp.exe".into(),
+            ..Default::default()
+        };
+        let expand = |arg: &str| source.expand(cpd, arg).unwrap();
+
+        let mut bin = OsString::from("-o");
+        bin.push(cpd.join("bin").join("ok.cpp.exe"));
+        assert_eq!(expand("-o%bin%"), bin);
+        assert_eq!(expand("%source%"), source.source.as_os_str());
+        assert_eq!(expand("%dir%"), cpd.join("bin").as_os_str());
+        assert_eq!(expand("%stem%.o"), "ok.o");
+        assert_eq!(expand("%problem%"), cpd.file_name().unwrap());
+        assert_eq!(expand("%time_limit%s %memory_limit%MB"), "2.5s 128MB");
+        assert_eq!(expand("100% %unknown% $5"), "100% %unknown% $5");
+        assert_eq!(expand("${RUSTYGON_UNSET_VARIABLE}x"), "x");
+        assert_eq!(
+            expand("${HOME}"),
+            var_os("HOME").unwrap_or_default().as_os_str()
+        );
+    }
+
+    #[test]
+    fn compares_modification_times() {
+        let dir = TempDir::new("more-recent");
+        create_dir(dir.path()).unwrap();
+        let source = dir.path().join("source");
+        let binary = dir.path().join("binary");
+
+        let error = more_recent(&source, &binary).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains(&*source.to_string_lossy()));
+
+        write(&source, "").unwrap();
+        assert!(more_recent(&source, &binary).unwrap());
+        write(&binary, "").unwrap();
+        assert!(!more_recent(&source, &binary).unwrap());
+    }
+}
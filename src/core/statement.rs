@@ -0,0 +1,382 @@
+use super::{modify_config, GenericResult, ProblemConfig};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env::var_os;
+use std::error::Error;
+use std::fs::{copy, create_dir_all, read_to_string, remove_dir_all, remove_file, rename, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The language of the first statement of a problem if none is given.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The statement of the problem in one language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Statement {
+    /// A language code such as "en", "ru" or "ar".
+    pub language: String,
+    /// The language this statement is translated from, or `None` for an original statement.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The hash of every section of the source as of the last update of its translation.
+    /// A section is stale once the hash of its source changes.
+    #[serde(default)]
+    pub synced: BTreeMap<String, u64>,
+}
+
+/// A section of the statement, written in Markdown with LaTeX math between `$` or `$$`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Section {
+    /// The story and the task.
+    Legend,
+    /// The format of the input.
+    Input,
+    /// The format of the output.
+    Output,
+    /// How the subtasks are scored.
+    Scoring,
+    /// Remarks shown after the samples, such as explanations of them.
+    Notes,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::Legend,
+        Section::Input,
+        Section::Output,
+        Section::Scoring,
+        Section::Notes,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Legend => "legend",
+            Section::Input => "input",
+            Section::Output => "output",
+            Section::Notes => "notes",
+            Section::Scoring => "scoring",
+        }
+    }
+
+    /// The title the section is rendered under, if any.
+    pub fn title(&self) -> Option<&'static str> {
+        match self {
+            Section::Legend => None,
+            Section::Input => Some("Input"),
+            Section::Output => Some("Output"),
+            Section::Notes => Some("Notes"),
+            Section::Scoring => Some("Scoring"),
+        }
+    }
+
+    fn template(&self) -> &'static str {
+        match self {
+            Section::Legend => {
+                "<!-- The story and the task. Write math as $a_i \\le 10^9$ or $$\\sum_{i=1}^{n} a_i$$. -->\n"
+            }
+            Section::Input => "<!-- The format of the input and the constraints. -->\n",
+            Section::Output => "<!-- The format of the output. -->\n",
+            Section::Notes => "<!-- Explanations of the samples. Leave empty to omit. -->\n",
+            Section::Scoring => "<!-- The subtasks and their points. Leave empty to omit. -->\n",
+        }
+    }
+}
+
+/// The directory of the statement in `language`, such as "{cpd}/text/statement/en".
+pub fn statement_dir(cpd: &Path, language: &str) -> PathBuf {
+    cpd.join("text").join("statement").join(language)
+}
+
+/// The file of a section, such as "{cpd}/text/statement/en/legend.md".
+pub fn section_path(cpd: &Path, language: &str, section: Section) -> PathBuf {
+    statement_dir(cpd, language).join(format!("{}.md", section.name()))
+}
+
+/// Whether `language` can be used as a language code, such as "en" or "pt-br".
+pub fn is_valid_language(language: &str) -> bool {
+    !language.is_empty()
+        && language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Finds the statement in `language`, or the first statement of the problem if `None`.
+pub fn find_statement<'a>(
+    config: &'a ProblemConfig,
+    language: Option<&str>,
+) -> Result<&'a Statement, String> {
+    match language {
+        Some(language) => config
+            .statements
+            .iter()
+            .find(|statement| statement.language == language)
+            .ok_or(format!("The problem has no statement in {language}")),
+        None => config
+            .statements
+            .first()
+            .ok_or("The problem has no statement".to_string()),
+    }
+}
+
+/// Moves a statement written before statements had languages, whose sections are right in
+/// "text/statement", to the statement in [`DEFAULT_LANGUAGE`], which becomes the first one.
+/// Returns whether there was such a statement.
+pub fn migrate_statement(cpd: &Path) -> Result<bool, Box<dyn Error>> {
+    let old_path = |section: Section| {
+        cpd.join("text")
+            .join("statement")
+            .join(format!("{}.md", section.name()))
+    };
+    if !Section::ALL
+        .into_iter()
+        .any(|section| old_path(section).is_file())
+    {
+        return Ok(false);
+    }
+
+    modify_config(cpd, |config| {
+        if config
+            .statements
+            .iter()
+            .any(|statement| statement.language == DEFAULT_LANGUAGE)
+        {
+            return Err(format!(
+                "The sections in text/statement cannot be moved to the statement in {DEFAULT_LANGUAGE}, which already exists"
+            )
+            .into());
+        }
+
+        create_dir_all(statement_dir(cpd, DEFAULT_LANGUAGE))?;
+        for section in Section::ALL {
+            let old = old_path(section);
+            if old.is_file() {
+                rename(old, section_path(cpd, DEFAULT_LANGUAGE, section))?;
+            }
+        }
+        config.statements.insert(
+            0,
+            Statement {
+                language: DEFAULT_LANGUAGE.to_string(),
+                source: None,
+                synced: BTreeMap::new(),
+            },
+        );
+        Ok(())
+    })?;
+    Ok(true)
+}
+
+/// Creates the statement in `language`.
+///
+/// * `from` - if not None, the statement is a translation seeded with the sections of
+///   the statement in this language. Otherwise every section holds a short guide as an
+///   HTML comment.
+pub fn add_statement(cpd: &Path, language: &str, from: Option<&str>) -> GenericResult {
+    if !is_valid_language(language) {
+        return Err(format!("Invalid language {language}").into());
+    }
+
+    modify_config(cpd, |config| {
+        if config.statements.iter().any(|s| s.language == language) {
+            return Err(format!("The problem already has a statement in {language}").into());
+        }
+        if let Some(from) = from {
+            find_statement(config, Some(from))?;
+        }
+
+        create_dir_all(statement_dir(cpd, language))?;
+        let mut synced = BTreeMap::new();
+        for section in Section::ALL {
+            let path = section_path(cpd, language, section);
+            match from {
+                Some(from) => {
+                    let source = section_path(cpd, from, section);
+                    if source.exists() {
+                        copy(&source, &path)?;
+                    }
+                    synced.insert(
+                        section.name().to_string(),
+                        section_hash(cpd, from, section)?,
+                    );
+                }
+                None => write(path, section.template())?,
+            }
+        }
+
+        config.statements.push(Statement {
+            language: language.to_string(),
+            source: from.map(str::to_string),
+            synced,
+        });
+        Ok(())
+    })
+}
+
+/// Replaces the content of a section with the content of `from`.
+/// The section of a translation is then no longer stale.
+pub fn set_section(cpd: &Path, language: &str, section: Section, from: &Path) -> GenericResult {
+    mark_synced(cpd, language, section, |path| {
+        copy(from, path)?;
+        Ok(())
+    })
+}
+
+/// Opens a section in "$EDITOR", or vi if it is not set.
+/// The section is created empty if it does not exist.
+/// The section of a translation is then no longer stale.
+pub fn edit_section(cpd: &Path, language: &str, section: Section) -> GenericResult {
+    mark_synced(cpd, language, section, |path| {
+        if !path.exists() {
+            write(path, "")?;
+        }
+        open_editor(path)
+    })
+}
+
+/// Opens a file in "$EDITOR", or vi if it is not set, and waits for the editor to exit.
+pub fn open_editor(path: &Path) -> GenericResult {
+    let editor = var_os("EDITOR").unwrap_or("vi".into());
+    let status = Command::new(&editor).arg(path).status()?;
+    if !status.success() {
+        return Err(format!("{} exited with {status}", editor.to_string_lossy()).into());
+    }
+    Ok(())
+}
+
+/// Updates a section with `update`, then records the current hash of its source.
+fn mark_synced(
+    cpd: &Path,
+    language: &str,
+    section: Section,
+    mut update: impl FnMut(&Path) -> GenericResult,
+) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .statements
+            .iter()
+            .position(|statement| statement.language == language)
+            .ok_or(format!("The problem has no statement in {language}"))?;
+
+        update(&section_path(cpd, language, section))?;
+
+        let statement = &mut config.statements[pos];
+        if let Some(source) = &statement.source {
+            let hash = section_hash(cpd, source, section)?;
+            statement.synced.insert(section.name().to_string(), hash);
+        }
+        Ok(())
+    })
+}
+
+/// Removes a section of the statement in `language`, or the whole statement if
+/// `section` is `None`. A statement that others are translated from is not removed.
+pub fn remove_statement(cpd: &Path, language: &str, section: Option<Section>) -> GenericResult {
+    modify_config(cpd, |config| {
+        let pos = config
+            .statements
+            .iter()
+            .position(|statement| statement.language == language)
+            .ok_or(format!("The problem has no statement in {language}"))?;
+
+        match section {
+            Some(section) => {
+                let path = section_path(cpd, language, section);
+                if !path.exists() {
+                    return Err(format!(
+                        "The statement in {language} has no {} section",
+                        section.name()
+                    )
+                    .into());
+                }
+                remove_file(path)?;
+            }
+            None => {
+                if let Some(translation) = config
+                    .statements
+                    .iter()
+                    .find(|statement| statement.source.as_deref() == Some(language))
+                {
+                    return Err(format!(
+                        "The statement in {} is translated from {language}",
+                        translation.language
+                    )
+                    .into());
+                }
+
+                let dir = statement_dir(cpd, language);
+                if dir.exists() {
+                    remove_dir_all(dir)?;
+                }
+                config.statements.remove(pos);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Returns the sections of a translation that changed in its source since they were
+/// last updated. An original statement is never stale.
+pub fn stale_sections(cpd: &Path, statement: &Statement) -> io::Result<Vec<Section>> {
+    let Some(source) = &statement.source else {
+        return Ok(Vec::new());
+    };
+
+    let mut stale = Vec::new();
+    for section in Section::ALL {
+        let hash = section_hash(cpd, source, section)?;
+        if statement.synced.get(section.name()) != Some(&hash) {
+            stale.push(section);
+        }
+    }
+    Ok(stale)
+}
+
+/// The FNV-1a hash of a section, which is stable across builds unlike the std hashers.
+/// A missing section hashes like an empty one.
+fn section_hash(cpd: &Path, language: &str, section: Section) -> io::Result<u64> {
+    let path = section_path(cpd, language, section);
+    let text = if path.exists() {
+        read_to_string(path)?
+    } else {
+        String::new()
+    };
+
+    Ok(text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    }))
+}
+
+/// Reads the sections of the statement in `language`, in order, without their HTML comments.
+/// Sections that are missing or hold nothing but comments are left out.
+pub fn read_sections(cpd: &Path, language: &str) -> Result<Vec<(Section, String)>, Box<dyn Error>> {
+    let mut sections = Vec::new();
+    for section in Section::ALL {
+        let path = section_path(cpd, language, section);
+        if !path.exists() {
+            continue;
+        }
+
+        let text = strip_comments(&read_to_string(path)?);
+        if !text.trim().is_empty() {
+            sections.push((section, text));
+        }
+    }
+    Ok(sections)
+}
+
+/// Removes the HTML comments, such as the guides of the templates, from Markdown.
+pub fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<!--") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start..].find("-->") {
+            Some(end) => &rest[start + end + 3..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
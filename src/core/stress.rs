@@ -0,0 +1,187 @@
+use super::checker::{build_checker, check};
+use super::generate::{find_generator, GeneratorCall};
+use super::solution::Verdict;
+use super::source::{RunOptions, SourceFile};
+use super::validate::validate_input;
+use super::ProblemConfig;
+use std::error::Error;
+use std::fs::create_dir_all;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+
+/// A generated test on which the second solution disagrees with the first one.
+#[derive(Debug, Clone)]
+pub struct Counterexample {
+    /// The generator call producing the test, whose last argument is the seed.
+    pub call: GeneratorCall,
+    /// The verdict of the second solution, taking the first one as correct.
+    pub verdict: Verdict,
+    pub message: String,
+    /// The file holding the generated input.
+    pub input: PathBuf,
+}
+
+/// Compares the solutions `first` and `second` on tests generated by `call`
+/// with the seeds `seed`, `seed + 1`, ... appended to its arguments.
+///
+/// The output of `first` is taken as the answer, and the output of `second` is judged
+/// against it with the checker of the problem. Stops at the first test `second` fails,
+/// or after `iterations` tests if given. Generated tests are validated if the problem
+/// has a validator. The files of the last test are kept in "{cpd}/bin/stress".
+pub fn stress(
+    cpd: &Path,
+    config: &ProblemConfig,
+    call: &GeneratorCall,
+    first: &str,
+    second: &str,
+    seed: u64,
+    iterations: Option<u64>,
+) -> Result<Option<Counterexample>, Box<dyn Error>> {
+    if config.interactive {
+        return Err("Stress testing interactive problems is not supported".into());
+    }
+
+    let generator = find_generator(config, &call.generator)
+        .map(|i| &config.sources[i])
+        .ok_or(format!(
+            "{} was not found in problem_config.json",
+            call.generator
+        ))?;
+    let validator = config.validator.map(|i| &config.sources[i]);
+    let first = find_solution(config, first)?;
+    let second = find_solution(config, second)?;
+
+    for program in [Some(generator), validator, Some(first), Some(second)]
+        .into_iter()
+        .flatten()
+    {
+        print!("Building {:#?}...", program.source.file_name().unwrap());
+        program.build(cpd)?;
+        println!("Done");
+    }
+    build_checker(cpd, config)?;
+
+    let dir = cpd.join("bin").join("stress");
+    create_dir_all(&dir)?;
+    let programs = Programs {
+        generator,
+        validator,
+        first,
+        second,
+    };
+
+    let seeds = seed..iterations.map_or(u64::MAX, |n| seed.saturating_add(n));
+    for seed in seeds {
+        print!("\rSeed {seed}");
+        stdout().flush()?;
+
+        let mut call = call.clone();
+        call.args.push(seed.to_string());
+
+        let verdict = run_once(cpd, config, &programs, &call, &dir);
+        if !matches!(verdict, Ok(None)) {
+            println!();
+        }
+        if let Some((verdict, message)) = verdict? {
+            return Ok(Some(Counterexample {
+                call,
+                verdict,
+                message,
+                input: dir.join("input"),
+            }));
+        }
+    }
+
+    println!();
+    Ok(None)
+}
+
+struct Programs<'a> {
+    generator: &'a SourceFile,
+    validator: Option<&'a SourceFile>,
+    first: &'a SourceFile,
+    second: &'a SourceFile,
+}
+
+/// Generates a single test with `call` into `dir` and runs both solutions on it.
+/// Returns the verdict of the second solution and its reason if it is not AC.
+fn run_once(
+    cpd: &Path,
+    config: &ProblemConfig,
+    programs: &Programs,
+    call: &GeneratorCall,
+    dir: &Path,
+) -> Result<Option<(Verdict, String)>, Box<dyn Error>> {
+    let input = dir.join("input");
+    let answer = dir.join("answer");
+    let output = dir.join("output");
+    let limits = config.limits();
+    let helper_limits = config.helper_limits();
+
+    let options = RunOptions {
+        args: call.args.iter().map(Into::into).collect(),
+        stdout: Some(input.clone()),
+        ..Default::default()
+    };
+    let report = programs.generator.run(cpd, &helper_limits, &options)?;
+    if report.time_exceeded(&helper_limits) {
+        return Err(format!(
+            "{call} exceeded the time limit of {:.1}s",
+            helper_limits.time.unwrap().as_secs_f32()
+        )
+        .into());
+    }
+    if let Some(crash) = report.crash() {
+        return Err(format!("{call} crashed with {crash}").into());
+    }
+
+    if let Some(validator) = programs.validator {
+        let stderr = input.with_extension("err");
+        if let Some(reason) = validate_input(cpd, validator, &helper_limits, &input, None, &stderr)?
+        {
+            return Err(format!("{call} generated an invalid test: {reason}").into());
+        }
+    }
+
+    let options = RunOptions {
+        stdin: Some(input.clone()),
+        stdout: Some(answer.clone()),
+        stderr: Some(answer.with_extension("err")),
+        ..Default::default()
+    };
+    let report = programs.first.run(cpd, &limits, &options)?;
+    let first = programs.first.source.file_name().unwrap().to_string_lossy();
+    if let Some(verdict) = report.limit_verdict(&limits) {
+        return Err(format!("{first} got {verdict} on {call}").into());
+    }
+    if let Some(crash) = report.crash() {
+        return Err(format!("{first} crashed on {call} with {crash}").into());
+    }
+
+    let options = RunOptions {
+        stdin: Some(input.clone()),
+        stdout: Some(output.clone()),
+        stderr: Some(output.with_extension("err")),
+        ..Default::default()
+    };
+    let report = programs.second.run(cpd, &limits, &options)?;
+    let (verdict, message) = if let Some(verdict) = report.limit_verdict(&limits) {
+        (verdict, format!("The solution got {verdict}"))
+    } else if let Some(crash) = report.crash() {
+        (Verdict::RE, format!("The solution crashed with {crash}"))
+    } else {
+        let checked = check(cpd, config, &input, &output, &answer)?;
+        (checked.verdict.verdict(), checked.message)
+    };
+
+    Ok((verdict != Verdict::AC).then_some((verdict, message)))
+}
+
+fn find_solution<'a>(config: &'a ProblemConfig, name: &str) -> Result<&'a SourceFile, String> {
+    config
+        .solutions
+        .iter()
+        .map(|solution| &solution.sourcefile)
+        .find(|source| source.source.file_name().unwrap().eq(name))
+        .ok_or(format!("{name} was not found in problem_config.json"))
+}
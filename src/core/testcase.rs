@@ -0,0 +1,204 @@
+use super::{modify_config, GenericResult};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::{copy, read_dir, remove_file, rename, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Testcase {
+    pub input_path: PathBuf,
+    pub output_path: PathBuf,
+    pub generate: bool,
+    pub sample: bool,
+    /// The name of the group the testcase belongs to
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// Returns the input and output paths of the `n`th testcase (1-based).
+/// Testcases are numbered "{cpd}/testcases/input/NN" and "{cpd}/testcases/output/NN".
+pub fn testcase_paths(cpd: &Path, n: usize) -> (PathBuf, PathBuf) {
+    let name = format!("{n:02}");
+    (
+        cpd.join("testcases/input").join(&name),
+        cpd.join("testcases/output").join(&name),
+    )
+}
+
+/// Adds a testcase to the end of the problem's testcases.
+///
+/// * `cpd`    - The problem directory to which we want to add the testcase
+/// * `from`   - if not None. the input is copied from this file, otherwise it is read from stdin.
+/// * `sample` - Whether the testcase is shown in the statement.
+///
+/// Returns the number of the added testcase.
+pub fn add_test(cpd: &Path, from: Option<&Path>, sample: bool) -> Result<usize, Box<dyn Error>> {
+    let mut n = 0;
+    modify_config(cpd, |config| {
+        n = config.testcases.len() + 1;
+        let (input_path, output_path) = testcase_paths(cpd, n);
+
+        match from {
+            Some(path) => {
+                copy(path, &input_path)?;
+            }
+            None => {
+                io::copy(&mut io::stdin().lock(), &mut File::create(&input_path)?)?;
+            }
+        }
+
+        config.testcases.push(Testcase {
+            input_path,
+            output_path,
+            generate: false,
+            sample,
+            group: None,
+        });
+        Ok(())
+    })?;
+    Ok(n)
+}
+
+/// Removes the `n`th testcase (1-based) and renumbers the testcases after it.
+pub fn remove_test(cpd: &Path, n: usize) -> GenericResult {
+    modify_config(cpd, |config| {
+        if n == 0 || n > config.testcases.len() {
+            return Err(format!("Test {n} does not exist").into());
+        }
+
+        let testcase = config.testcases.remove(n - 1);
+        remove_file(&testcase.input_path)?;
+        if testcase.output_path.exists() {
+            remove_file(&testcase.output_path)?;
+        }
+
+        for (i, testcase) in config.testcases.iter_mut().enumerate().skip(n - 1) {
+            let (input_path, output_path) = testcase_paths(cpd, i + 1);
+            rename(&testcase.input_path, &input_path)?;
+            if testcase.output_path.exists() {
+                rename(&testcase.output_path, &output_path)?;
+            }
+            testcase.input_path = input_path;
+            testcase.output_path = output_path;
+        }
+        Ok(())
+    })
+}
+
+/// Marks the `n`th testcase (1-based) as a sample or not.
+pub fn set_sample(cpd: &Path, n: usize, sample: bool) -> GenericResult {
+    modify_config(cpd, |config| {
+        let testcase = n
+            .checked_sub(1)
+            .and_then(|i| config.testcases.get_mut(i))
+            .ok_or(format!("Test {n} does not exist"))?;
+
+        testcase.sample = sample;
+        Ok(())
+    })
+}
+
+/// Expands `path` into the files it refers to, sorted by name.
+///
+/// `path` is either a file, a directory (meaning every file in it)
+/// or a file name pattern where `*` and `?` are wildcards, e.g. "tests/*.in".
+pub fn expand_test_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let (dir, pattern) = if path.is_dir() {
+        (path, "*")
+    } else {
+        let pattern = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        (dir.unwrap_or(Path::new(".")), pattern)
+    };
+
+    let mut files = Vec::new();
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| wildcard_match(pattern.as_bytes(), name.as_bytes()));
+
+        if matches && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name))) => wildcard_match(rest, name),
+        (Some((p, rest)), Some((c, name))) => p == c && wildcard_match(rest, name),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::temp_problem;
+    use crate::core::ProblemConfig;
+    use std::fs::{read_to_string, write};
+
+    #[test]
+    fn matches_wildcards() {
+        let matches =
+            |pattern: &str, name: &str| wildcard_match(pattern.as_bytes(), name.as_bytes());
+        assert!(matches("*.in", "01.in"));
+        assert!(!matches("*.in", "01.out"));
+        assert!(matches("t??", "t01"));
+        assert!(!matches("t??", "t1"));
+        assert!(matches("a*b*c", "axxbyc"));
+        assert!(!matches("a*b*c", "axxbyd"));
+        assert!(matches("*", ""));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn renumbers_the_tests_after_a_removed_one() {
+        let problem = temp_problem("remove-test", 1.0, 256);
+        let cpd = problem.path();
+
+        for n in 1..=3 {
+            let input = cpd.join(format!("input{n}"));
+            write(&input, format!("{n}")).unwrap();
+            add_test(cpd, Some(&input), false).unwrap();
+        }
+        // The second test has no output.
+        write(testcase_paths(cpd, 1).1, "1a").unwrap();
+        write(testcase_paths(cpd, 3).1, "3a").unwrap();
+
+        remove_test(cpd, 2).unwrap();
+
+        let file = File::open(cpd.join("problem_config.json")).unwrap();
+        let config = ProblemConfig::from_file(file).unwrap();
+        assert_eq!(config.testcases.len(), 2);
+        for (i, testcase) in config.testcases.iter().enumerate() {
+            assert_eq!(
+                (&testcase.input_path, &testcase.output_path),
+                (&testcase_paths(cpd, i + 1).0, &testcase_paths(cpd, i + 1).1)
+            );
+        }
+        let (input, output) = testcase_paths(cpd, 2);
+        assert_eq!(read_to_string(input).unwrap(), "3");
+        assert_eq!(read_to_string(output).unwrap(), "3a");
+        assert!(!testcase_paths(cpd, 3).0.exists());
+        assert!(!testcase_paths(cpd, 3).1.exists());
+        assert!(remove_test(cpd, 3).is_err());
+    }
+}
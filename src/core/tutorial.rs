@@ -0,0 +1,114 @@
+use super::statement::{is_valid_language, open_editor, strip_comments};
+use super::{GenericResult, ProblemConfig};
+use std::error::Error;
+use std::fs::{copy, create_dir_all, read_to_string, remove_file, write};
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str =
+    "<!-- The tutorial of the problem, in Markdown with math as $a_i$ or $$\\sum a_i$$.
+A line holding only {{solution name}} is replaced by the source of the solution \"name\". -->
+";
+
+/// The tutorial in `language`, such as "{cpd}/text/tutorial/en.md".
+pub fn tutorial_path(cpd: &Path, language: &str) -> PathBuf {
+    cpd.join("text")
+        .join("tutorial")
+        .join(format!("{language}.md"))
+}
+
+/// Creates the tutorial in `language` holding a short guide as an HTML comment.
+pub fn add_tutorial(cpd: &Path, language: &str) -> GenericResult {
+    if !is_valid_language(language) {
+        return Err(format!("Invalid language {language}").into());
+    }
+
+    let path = tutorial_path(cpd, language);
+    if path.exists() {
+        return Err(format!("The problem already has a tutorial in {language}").into());
+    }
+    create_dir_all(path.parent().unwrap())?;
+    write(path, TEMPLATE)?;
+    Ok(())
+}
+
+/// Replaces the tutorial in `language` with the content of `from`, or opens it in
+/// an editor if `from` is `None`.
+pub fn set_tutorial(cpd: &Path, language: &str, from: Option<&Path>) -> GenericResult {
+    let path = tutorial_path(cpd, language);
+    create_dir_all(path.parent().unwrap())?;
+    match from {
+        Some(from) => {
+            copy(from, path)?;
+            Ok(())
+        }
+        None => {
+            if !path.exists() {
+                write(&path, "")?;
+            }
+            open_editor(&path)
+        }
+    }
+}
+
+pub fn remove_tutorial(cpd: &Path, language: &str) -> GenericResult {
+    let path = tutorial_path(cpd, language);
+    if !path.exists() {
+        return Err(format!("The problem has no tutorial in {language}").into());
+    }
+    remove_file(path)?;
+    Ok(())
+}
+
+/// Reads the tutorial in `language` without its HTML comments, with the sources
+/// of the solutions it embeds.
+pub fn read_tutorial(
+    cpd: &Path,
+    config: &ProblemConfig,
+    language: &str,
+) -> Result<String, Box<dyn Error>> {
+    let path = tutorial_path(cpd, language);
+    if !path.exists() {
+        return Err(format!("The problem has no tutorial in {language}").into());
+    }
+    embed_solutions(config, &strip_comments(&read_to_string(path)?))
+}
+
+/// Replaces every line holding only `{{solution name}}` with a code block holding
+/// the current source of the solution `name`.
+pub fn embed_solutions(config: &ProblemConfig, text: &str) -> Result<String, Box<dyn Error>> {
+    let mut result = String::with_capacity(text.len());
+    for line in text.lines() {
+        let Some(name) = line
+            .trim()
+            .strip_prefix("{{solution ")
+            .and_then(|rest| rest.strip_suffix("}}"))
+        else {
+            result.push_str(line);
+            result.push('\n');
+            continue;
+        };
+
+        let name = name.trim();
+        let source = &config
+            .solutions
+            .iter()
+            .find(|solution| solution.sourcefile.source.file_name().unwrap().eq(name))
+            .ok_or(format!("{name} was not found in problem_config.json"))?
+            .sourcefile
+            .source;
+        let code = read_to_string(source)?;
+
+        // The fence must be longer than any run of backticks in the source.
+        let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        let language = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+        result.push_str(&format!("{fence}{language}\n{code}"));
+        if !code.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(&fence);
+        result.push('\n');
+    }
+    Ok(result)
+}
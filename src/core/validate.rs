@@ -0,0 +1,105 @@
+use super::source::{Limits, RunOptions, SourceFile};
+use super::ProblemConfig;
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string};
+use std::io;
+use std::path::Path;
+
+/// Builds the validator and runs it on the input of every testcase.
+///
+/// The input is piped to the validator, which rejects it by exiting with a non-zero code.
+/// Returns for every testcase `None` if it is valid, or the reason it is not,
+/// taken from the standard error of the validator.
+pub fn validate_tests(
+    cpd: &Path,
+    config: &ProblemConfig,
+) -> Result<Vec<Option<String>>, Box<dyn Error>> {
+    let validator = config
+        .validator
+        .map(|i| &config.sources[i])
+        .ok_or("The problem has no validator")?;
+
+    print!(
+        "Building validator {:#?}...",
+        validator.source.file_name().unwrap()
+    );
+    validator.build(cpd)?;
+    println!("Done");
+
+    let log_dir = cpd.join("bin").join("validation");
+    create_dir_all(&log_dir)?;
+
+    let limits = config.helper_limits();
+    let mut results = Vec::with_capacity(config.testcases.len());
+    for (i, testcase) in config.testcases.iter().enumerate() {
+        let stderr = log_dir.join(format!("{:02}.err", i + 1));
+        let group = testcase.group.as_deref();
+        results.push(validate_input(
+            cpd,
+            validator,
+            &limits,
+            &testcase.input_path,
+            group,
+            &stderr,
+        )?);
+    }
+
+    Ok(results)
+}
+
+/// Runs the already built `validator` on `input`, keeping its standard error in `stderr`.
+/// The group of the testcase, if any, is passed as `--group <name>` like testlib expects.
+/// Returns `None` if the input is valid, or the reason it is not.
+/// A validator running past `limits` is an error.
+pub fn validate_input(
+    cpd: &Path,
+    validator: &SourceFile,
+    limits: &Limits,
+    input: &Path,
+    group: Option<&str>,
+    stderr: &Path,
+) -> io::Result<Option<String>> {
+    let options = RunOptions {
+        args: group
+            .map(|group| vec!["--group".into(), group.into()])
+            .unwrap_or_default(),
+        stdin: Some(input.to_path_buf()),
+        stderr: Some(stderr.to_path_buf()),
+        ..Default::default()
+    };
+    let report = validator.run(cpd, limits, &options)?;
+    if report.time_exceeded(limits) {
+        return Err(io::Error::other(format!(
+            "The validator exceeded the time limit of {:.1}s on {}",
+            limits.time.unwrap().as_secs_f32(),
+            input.display()
+        )));
+    }
+
+    Ok(report.crash().map(|crash| {
+        let message = read_to_string(stderr).unwrap_or_default();
+        match message.trim() {
+            "" => format!("The validator exited with {crash}"),
+            message => message.to_string(),
+        }
+    }))
+}
+
+/// Fails if the problem has a validator and any testcase input is rejected by it.
+pub fn ensure_valid(cpd: &Path, config: &ProblemConfig) -> Result<(), Box<dyn Error>> {
+    if config.validator.is_none() {
+        return Ok(());
+    }
+
+    let invalid: Vec<String> = validate_tests(cpd, config)?
+        .iter()
+        .enumerate()
+        .filter(|(_, error)| error.is_some())
+        .map(|(i, _)| (i + 1).to_string())
+        .collect();
+
+    match invalid.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Invalid tests: {}", invalid.join(", ")).into()),
+    }
+}
@@ -1,12 +1,14 @@
 use clap::Parser;
 use rustygon::cli::{handle_command, Command};
+use rustygon::core::config::Config;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None, arg_required_else_help=true)]
 pub struct Cli {
-    // Sets a custom config file
-    //#[arg(short, long, value_name = "FILE")]
-    //pub config: Option<PathBuf>,
+    /// Sets a custom config file, instead of ~/.config/rustygon/config.json
+    #[arg(short, long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
     /// Turn debugging information on
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub debug: u8,
@@ -14,23 +16,14 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Command>,
 }
-// TODO config
-//#[derive(Default,Serialize, Deserialize)]
-//struct Config {
-//}
 
 fn main() {
     let args = Cli::parse();
-    //let config_path = args.config.unwrap_or(
-    //    current_dir()
-    //        .unwrap()
-    //        .to_path_buf()
-    //        .join("rustygon_config.json"),
-    //);
+    let config = match (args.config, Config::user_path()) {
+        (Some(path), _) => Config::load(&path).unwrap(),
+        (None, Some(path)) if path.exists() => Config::load(&path).unwrap(),
+        _ => Config::default(),
+    };
 
-    //let config = match config_path.exists() {
-    //    true => Config::load_from_json(File::open(config_path)?)?,
-    //    false => Config::default(),
-    //};
-    handle_command(args.command);
+    handle_command(args.command, config);
 }